@@ -0,0 +1,3 @@
+//! Shared library build of `sudoku-tester`, the bindings themselves live in
+//! its `wasm` and `ffi` modules
+pub use sudoku_tester::*;
@@ -0,0 +1,25 @@
+//! Checks that a `no_std` crate can depend on `sudoku-tester` without `std`
+//! and still run its tests on the host
+#![no_std]
+
+use sudoku_tester::Sudoku;
+
+/// Whether `line` holds a finished grid without any duplicate
+pub fn is_solved(line: &str) -> bool {
+    Sudoku::from_line(line).is_ok_and(|sudoku| sudoku.validate_bounded::<1>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_solved() {
+        assert!(is_solved(
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179"
+        ));
+        assert!(!is_solved(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79"
+        ));
+    }
+}
@@ -0,0 +1,36 @@
+/* C interface of the sudoku-tester crate, built with `cargo build -p sudoku-tester-bindings --features ffi` */
+#ifndef SUDOKU_TESTER_H
+#define SUDOKU_TESTER_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Opaque grid, release every grid returned by the library with sudoku_free */
+typedef struct Sudoku Sudoku;
+
+typedef enum SudokuStatus {
+    SUDOKU_OK = 0,
+    SUDOKU_NULL_POINTER = 1,
+    SUDOKU_INVALID_UTF8 = 2,
+    SUDOKU_PARSE_ERROR = 3,
+    SUDOKU_INVALID = 4,
+    SUDOKU_UNSOLVABLE = 5,
+} SudokuStatus;
+
+/* Parse the one-line format or the 9-line grid, '0' or '.' for empty cells */
+SudokuStatus sudoku_parse(const char *input, Sudoku **out);
+/* SUDOKU_OK when there are no duplicates, error_count may be NULL */
+SudokuStatus sudoku_validate(const Sudoku *sudoku, size_t *error_count);
+SudokuStatus sudoku_solve(const Sudoku *sudoku, Sudoku **out);
+/* buffer must hold at least 82 bytes: 81 cells and the terminating NUL */
+SudokuStatus sudoku_write_line(const Sudoku *sudoku, char *buffer);
+void sudoku_free(Sudoku *sudoku);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* SUDOKU_TESTER_H */
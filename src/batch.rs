@@ -0,0 +1,160 @@
+//! Validation and solving of large puzzle collections across all cores
+//!
+//! Any parallel iterator of grids is accepted, streaming ones too, such as the
+//! lines of a huge file through [`ParallelBridge::par_bridge`]. Results come
+//! back in the order of that iterator: for a `Vec`, a slice or any other indexed
+//! source, the `n`-th result always belongs to the `n`-th puzzle, no matter which
+//! thread handled it. `par_bridge` makes no such promise, so keep an id next to
+//! each grid there when the results have to be matched back to the input.
+//!
+//! The plain functions run on the global rayon pool, their `_with` variants take
+//! a [`Parallelism`] to stay within the CPU budget of the embedding application
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+use crate::{Sudoku, ValidationError};
+
+/// Threads the batch functions run on
+#[derive(Debug, Default)]
+pub enum Parallelism<'a> {
+    /// The global rayon pool, shared with the rest of the process
+    #[default]
+    Global,
+    /// A pool managed by the application
+    Pool(&'a ThreadPool),
+    /// A pool of our own, see [`Parallelism::max_threads`]
+    Owned(ThreadPool),
+}
+
+impl Parallelism<'static> {
+    /// Dedicated pool of at most `threads` threads, built once and reused by
+    /// every call it's passed to. `0` lets rayon pick, as for the global pool
+    pub fn max_threads(threads: usize) -> Result<Self, ThreadPoolBuildError> {
+        ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .map(Self::Owned)
+    }
+}
+
+impl Parallelism<'_> {
+    fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        match self {
+            Self::Global => op(),
+            Self::Pool(pool) => pool.install(op),
+            Self::Owned(pool) => pool.install(op),
+        }
+    }
+}
+
+/// [`Sudoku::validate`] every grid in parallel
+pub fn validate_all<I>(sudokus: I) -> Vec<Result<Sudoku, Vec<ValidationError>>>
+where
+    I: IntoParallelIterator<Item = Sudoku> + Send,
+{
+    validate_all_with(sudokus, &Parallelism::Global)
+}
+
+/// [`validate_all`] on the threads of `parallelism`
+pub fn validate_all_with<I>(
+    sudokus: I,
+    parallelism: &Parallelism,
+) -> Vec<Result<Sudoku, Vec<ValidationError>>>
+where
+    I: IntoParallelIterator<Item = Sudoku> + Send,
+{
+    parallelism.install(|| sudokus.into_par_iter().map(Sudoku::validate).collect())
+}
+
+/// [`Sudoku::solve`] every puzzle in parallel, `None` for the unsolvable ones
+pub fn solve_all<I>(sudokus: I) -> Vec<Option<Sudoku>>
+where
+    I: IntoParallelIterator<Item = Sudoku> + Send,
+{
+    solve_all_with(sudokus, &Parallelism::Global)
+}
+
+/// [`solve_all`] on the threads of `parallelism`
+pub fn solve_all_with<I>(sudokus: I, parallelism: &Parallelism) -> Vec<Option<Sudoku>>
+where
+    I: IntoParallelIterator<Item = Sudoku> + Send,
+{
+    parallelism.install(|| {
+        sudokus
+            .into_par_iter()
+            .map(|sudoku| sudoku.solve())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_batch_preserves_order() {
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+        let mut broken = solution.clone();
+        broken[(0, 0)] = 3;
+        let grids = (0..100)
+            .map(|index| match index % 2 {
+                0 => solution.clone(),
+                _ => broken.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let validated = validate_all(grids.clone());
+        assert_eq!(validated.len(), 100);
+        assert!(validated
+            .iter()
+            .enumerate()
+            .all(|(index, result)| result.is_ok() == (index % 2 == 0)));
+
+        assert!(solve_all(grids)
+            .into_iter()
+            .enumerate()
+            .all(|(index, solved)| solved.is_some() == (index % 2 == 0)));
+    }
+
+    #[test]
+    fn test_batch_streaming() {
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+        let input = format!("{SOLUTION}\n").repeat(20);
+        let grids = input
+            .lines()
+            .par_bridge()
+            .map(|line| Sudoku::from_line(line).unwrap());
+
+        assert_eq!(solve_all(grids), vec![Some(solution); 20]);
+    }
+
+    #[test]
+    fn test_batch_parallelism() {
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+        let grids = vec![solution.clone(); 10];
+        let pool = ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+
+        for parallelism in [
+            Parallelism::default(),
+            Parallelism::Pool(&pool),
+            Parallelism::max_threads(1).unwrap(),
+        ] {
+            assert!(validate_all_with(grids.clone(), &parallelism)
+                .iter()
+                .all(Result::is_ok));
+            assert_eq!(
+                solve_all_with(grids.clone(), &parallelism),
+                vec![Some(solution.clone()); 10]
+            );
+        }
+
+        let single = Parallelism::max_threads(1).unwrap();
+        assert_eq!(single.install(rayon::current_num_threads), 1);
+        assert_eq!(
+            Parallelism::Pool(&pool).install(rayon::current_num_threads),
+            2
+        );
+    }
+}
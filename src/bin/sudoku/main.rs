@@ -0,0 +1,73 @@
+//! Command line front-end of the crate
+//!
+//! ```text
+//! sudoku play <file>                interactive game in the terminal (needs the `tui` feature)
+//! sudoku anonymize <file> [seed]    print an unrecognizable equivalent puzzle, without comments
+//! ```
+use std::{
+    env, fs,
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use sudoku_tester::Sudoku;
+
+#[cfg(feature = "tui")]
+mod play;
+
+const USAGE: &str = "usage: sudoku play <file>\n       sudoku anonymize <file> [seed]";
+
+/// Puzzle files hold either the one-line format or the 9-line grid,
+/// `#` comment lines are ignored
+fn load_puzzle(path: &str) -> Result<Sudoku, String> {
+    let content = fs::read_to_string(path).map_err(|err| format!("can't read {path}: {err}"))?;
+    let lines = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect::<Vec<_>>();
+
+    match lines.as_slice() {
+        [line] => Sudoku::from_line(line),
+        lines => lines.join("\n").parse(),
+    }
+    .map_err(|err| format!("can't parse {path}: {err:?}"))
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    match args {
+        [command, path] if command == "play" => start_game(load_puzzle(path)?),
+        [command, path, seed @ ..] if command == "anonymize" && seed.len() <= 1 => {
+            let seed = match seed.first() {
+                Some(seed) => seed.parse().map_err(|_| format!("invalid seed {seed}"))?,
+                None => SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_nanos() as u64),
+            };
+            println!("{}", load_puzzle(path)?.anonymize(seed).to_line());
+            Ok(())
+        }
+        _ => Err(USAGE.to_owned()),
+    }
+}
+
+#[cfg(feature = "tui")]
+fn start_game(puzzle: Sudoku) -> Result<(), String> {
+    play::run(puzzle).map_err(|err| format!("terminal error: {err}"))
+}
+
+#[cfg(not(feature = "tui"))]
+fn start_game(_: Sudoku) -> Result<(), String> {
+    Err("`play` is only available when built with the `tui` feature".to_owned())
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Terminal game: cursor movement, digit entry, pencil marks, conflict
+//! highlighting and hints, all backed by the library APIs
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use sudoku_tester::{
+    cell_char,
+    game::{MoveError, Session},
+    techniques::Hint,
+    RuleSet, Sudoku, ValidationError, EMPTY, SUDOKU_SIZE,
+};
+
+const HELP: &str =
+    "arrows/hjkl move  1-9 enter  0/del erase  p pencil  u undo  r redo  ? hint  q quit";
+
+/// Grid state lives in the [`Session`], the game only adds the cursor and the input mode
+struct Game {
+    session: Session,
+    cursor: (usize, usize),
+    pencil_mode: bool,
+    hint: Option<Hint>,
+    /// Cell of a digit the session refused and the cell already holding it,
+    /// highlighted until the next action
+    rejected: Option<[(usize, usize); 2]>,
+    message: String,
+}
+
+impl Game {
+    fn new(puzzle: Sudoku) -> Self {
+        Self {
+            session: Session::new(puzzle),
+            cursor: (0, 0),
+            pencil_mode: false,
+            hint: None,
+            rejected: None,
+            message: String::new(),
+        }
+    }
+
+    fn move_cursor(&mut self, rows: isize, cols: isize) {
+        let wrap = |value: usize, delta: isize| {
+            (value as isize + delta).rem_euclid(SUDOKU_SIZE as isize) as usize
+        };
+        self.cursor = (wrap(self.cursor.0, rows), wrap(self.cursor.1, cols));
+        self.rejected = None;
+    }
+
+    fn enter(&mut self, digit: u8) {
+        let played = match self.pencil_mode {
+            true => self.session.toggle_pencil(self.cursor, digit),
+            false => self.session.place(self.cursor, digit),
+        };
+        self.after(played, digit);
+    }
+
+    fn erase(&mut self) {
+        let played = self.session.erase(self.cursor);
+        self.after(played, EMPTY);
+    }
+
+    fn undo(&mut self) {
+        self.hint = None;
+        self.rejected = None;
+        self.message = match self.session.undo() {
+            Some(_) => String::new(),
+            None => "Nothing to undo".to_owned(),
+        };
+    }
+
+    fn redo(&mut self) {
+        self.hint = None;
+        self.rejected = None;
+        self.message = match self.session.redo() {
+            Some(_) => self.status(),
+            None => "Nothing to redo".to_owned(),
+        };
+    }
+
+    /// Status message once the session accepted or rejected a move with `digit`
+    fn after(&mut self, played: Result<(), MoveError>, digit: u8) {
+        self.rejected = match played {
+            Err(MoveError::Conflict { position }) => Some([self.cursor, position]),
+            _ => None,
+        };
+        self.message = match played {
+            Ok(()) => {
+                self.hint = None;
+                self.status()
+            }
+            Err(MoveError::Given) => "Givens can't be changed".to_owned(),
+            Err(MoveError::Conflict {
+                position: (row, col),
+            }) => format!("{digit} is already at r{}c{}", row + 1, col + 1),
+            Err(MoveError::OutOfGrid | MoveError::InvalidDigit) => "Invalid move".to_owned(),
+        };
+    }
+
+    fn status(&self) -> String {
+        match self.session.is_solved() {
+            true => "Solved!".to_owned(),
+            false => String::new(),
+        }
+    }
+
+    fn request_hint(&mut self) {
+        self.hint = self.session.grid().hint();
+        self.message = match &self.hint {
+            Some(Hint {
+                position: (row, col),
+                value,
+                technique,
+                ..
+            }) => format!("Hint: r{}c{} is {value} ({technique:?})", row + 1, col + 1),
+            None => "No logical move found".to_owned(),
+        };
+    }
+
+    /// Cells to show in red: those of the last refused move, and the ones
+    /// breaking a rule, which only a broken puzzle can have
+    fn conflicts(&self, grid: &Sudoku) -> Vec<(usize, usize)> {
+        RuleSet::standard()
+            .check(grid)
+            .iter()
+            .flat_map(ValidationError::cells)
+            .chain(self.rejected.into_iter().flatten())
+            .collect()
+    }
+
+    /// Line `line` (0 to 2) of a cell drawn as 3x3 characters: the value in
+    /// the middle, or the pencil marks each at the place of its digit
+    fn cell_line(&self, grid: &Sudoku, (row, col): (usize, usize), line: usize) -> String {
+        let marks = self.session.pencil_marks()[row][col];
+        match grid[(row, col)] {
+            EMPTY if marks == 0 => match line {
+                1 => " . ".to_owned(),
+                _ => "   ".to_owned(),
+            },
+            EMPTY => (1..=3)
+                .map(|i| {
+                    let digit = (line * 3 + i) as u8;
+                    match marks & (1 << (digit - 1)) {
+                        0 => ' ',
+                        _ => char::from(b'0' + digit),
+                    }
+                })
+                .collect(),
+            value => match line {
+                1 => format!(" {} ", cell_char(value)),
+                _ => "   ".to_owned(),
+            },
+        }
+    }
+
+    fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+        let separator = "+-------------+-------------+-------------+";
+        let grid = self.session.grid();
+        let conflicts = self.conflicts(&grid);
+        let hinted = |position| {
+            self.hint
+                .as_ref()
+                .is_some_and(|hint| hint.position == position || hint.cause.contains(&position))
+        };
+
+        for row in 0..SUDOKU_SIZE {
+            if row % 3 == 0 {
+                queue!(out, Print(separator), Print("\r\n"))?;
+            }
+            for line in 0..3 {
+                for col in 0..SUDOKU_SIZE {
+                    queue!(out, Print(if col % 3 == 0 { "| " } else { " " }))?;
+                    let position = (row, col);
+
+                    if position == self.cursor {
+                        queue!(out, SetAttribute(Attribute::Reverse))?;
+                    }
+                    if self.session.puzzle()[position] != EMPTY {
+                        queue!(out, SetAttribute(Attribute::Bold))?;
+                    }
+                    if conflicts.contains(&position) {
+                        queue!(out, SetForegroundColor(Color::Red))?;
+                    } else if hinted(position) {
+                        queue!(out, SetForegroundColor(Color::Green))?;
+                    }
+
+                    queue!(
+                        out,
+                        Print(self.cell_line(&grid, position, line)),
+                        ResetColor,
+                        SetAttribute(Attribute::Reset)
+                    )?;
+                }
+                queue!(out, Print(" |\r\n"))?;
+            }
+        }
+
+        let (row, col) = self.cursor;
+        let mode = match self.pencil_mode {
+            true => "pencil",
+            false => "digits",
+        };
+
+        queue!(
+            out,
+            Print(separator),
+            Print(format!(
+                "\r\n\r\nr{}c{}  mode: {mode}  moves: {}\r\n",
+                row + 1,
+                col + 1,
+                self.session.history().len()
+            )),
+            Print(&self.message),
+            Print("\r\n\r\n"),
+            Print(HELP)
+        )?;
+        out.flush()
+    }
+}
+
+/// Puts the terminal back into its normal state, even when the game panics
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+pub fn run(puzzle: Sudoku) -> io::Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    let mut out = io::stdout();
+    let mut game = Game::new(puzzle);
+
+    loop {
+        game.render(&mut out)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Up | KeyCode::Char('k') => game.move_cursor(-1, 0),
+            KeyCode::Down | KeyCode::Char('j') => game.move_cursor(1, 0),
+            KeyCode::Left | KeyCode::Char('h') => game.move_cursor(0, -1),
+            KeyCode::Right | KeyCode::Char('l') => game.move_cursor(0, 1),
+            KeyCode::Char(digit @ '1'..='9') => game.enter(digit as u8 - b'0'),
+            KeyCode::Char('0' | '.') | KeyCode::Backspace | KeyCode::Delete => game.erase(),
+            KeyCode::Char('p') => game.pencil_mode = !game.pencil_mode,
+            KeyCode::Char('u') => game.undo(),
+            KeyCode::Char('r') => game.redo(),
+            KeyCode::Char('?') => game.request_hint(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_game() {
+        let mut game = Game::new(Sudoku::from_line(PUZZLE).unwrap());
+
+        game.enter(1);
+        assert_eq!(game.session.grid()[(0, 0)], 5);
+        assert_eq!(game.message, "Givens can't be changed");
+
+        game.move_cursor(0, -7);
+        assert_eq!(game.cursor, (0, 2));
+        game.enter(5);
+        assert_eq!(game.session.grid()[(0, 2)], EMPTY);
+        assert_eq!(game.message, "5 is already at r1c1");
+        let conflicts = game.conflicts(&game.session.grid());
+        assert!(conflicts.contains(&(0, 2)) && conflicts.contains(&(0, 0)));
+
+        game.pencil_mode = true;
+        game.enter(4);
+        assert!(game.conflicts(&game.session.grid()).is_empty());
+        game.enter(1);
+        game.enter(1);
+        game.enter(2);
+        assert_eq!(game.session.pencil_marks()[0][2], 0b1010);
+        assert_eq!(game.cell_line(&game.session.grid(), (0, 2), 0), " 2 ");
+        assert_eq!(game.cell_line(&game.session.grid(), (0, 2), 1), "4  ");
+
+        // A placed digit replaces the pencil marks of its cell
+        game.pencil_mode = false;
+        game.enter(4);
+        assert_eq!(game.session.pencil_marks()[0][2], 0);
+        assert_eq!(game.cell_line(&game.session.grid(), (0, 2), 1), " 4 ");
+        game.undo();
+        assert_eq!(game.session.grid()[(0, 2)], EMPTY);
+        assert_eq!(game.session.pencil_marks()[0][2], 0b1010);
+
+        // Out of range values keep the cell 3 characters wide
+        let mut broken = Sudoku::from_line(PUZZLE).unwrap();
+        broken[(0, 0)] = 12;
+        let broken = Game::new(broken);
+        assert_eq!(broken.cell_line(&broken.session.grid(), (0, 0), 1), " ? ");
+
+        game.request_hint();
+        let hint = game.hint.unwrap();
+        assert_eq!(
+            game.session.grid().solve().unwrap()[hint.position],
+            hint.value
+        );
+    }
+}
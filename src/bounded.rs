@@ -0,0 +1,138 @@
+//! Validation into a fixed-capacity error list, for devices where every heap
+//! allocation counts.
+//!
+//! [`Sudoku::validate`] keeps returning a `Vec`: variant constraints may report
+//! any number of errors, each with any number of cells (see [`Indexes`](crate::Indexes)),
+//! and truncating them would hide violations. The standard checks are bounded
+//! though, a duplicate never spans more than the 9 cells of a house, so
+//! [`Sudoku::validate_bounded`] runs them without allocating and keeps the
+//! first `N` errors, flagging the list when more were found
+use core::slice;
+
+use crate::{unit_duplicates, Sudoku, ValidationError};
+
+/// Up to `N` errors stored inline, in the order [`Sudoku::validate`] reports them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorList<const N: usize> {
+    errors: [Option<ValidationError>; N],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<const N: usize> Default for ErrorList<N> {
+    fn default() -> Self {
+        Self {
+            errors: core::array::from_fn(|_| None),
+            len: 0,
+            overflowed: false,
+        }
+    }
+}
+
+impl<const N: usize> ErrorList<N> {
+    /// Append `error`, or only raise the overflow flag once the list is full
+    pub fn push(&mut self, error: ValidationError) {
+        match self.errors.get_mut(self.len) {
+            Some(slot) => {
+                *slot = Some(error);
+                self.len += 1;
+            }
+            None => self.overflowed = true,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0 && !self.overflowed
+    }
+
+    /// More errors were found than the list could hold, they were dropped
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ValidationError> {
+        self.errors[..self.len].iter().flatten()
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a ErrorList<N> {
+    type Item = &'a ValidationError;
+    type IntoIter = core::iter::Flatten<slice::Iter<'a, Option<ValidationError>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors[..self.len].iter().flatten()
+    }
+}
+
+impl Sudoku {
+    /// Same checks as [`Sudoku::validate`] without touching the heap, keeping
+    /// the first `N` errors
+    ///
+    /// ```
+    /// use sudoku_tester::{Sudoku, ValidationError};
+    ///
+    /// let mut sudoku = Sudoku::empty();
+    /// sudoku[(0, 0)] = 5;
+    /// sudoku[(0, 1)] = 5;
+    /// // Duplicates in the row and in the box, then the incomplete grid
+    /// let errors = sudoku.validate_bounded::<2>().unwrap_err();
+    /// assert!(errors
+    ///     .iter()
+    ///     .all(|error| matches!(error, ValidationError::Dublication { .. })));
+    /// assert!(errors.overflowed());
+    /// ```
+    pub fn validate_bounded<const N: usize>(self) -> Result<Self, ErrorList<N>> {
+        let mut errors = ErrorList::default();
+        self.range_errors()
+            .chain(
+                self.units()
+                    .flat_map(|(type_, cells)| unit_duplicates(type_, cells)),
+            )
+            .chain(self.incomplete_error())
+            .for_each(|error| errors.push(error));
+
+        match errors.is_empty() {
+            true => Ok(self),
+            false => Err(errors),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_validate_bounded() {
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+        assert_eq!(solution.clone().validate_bounded::<0>(), Ok(solution));
+
+        let mut broken = Sudoku::from_line(PUZZLE).unwrap();
+        broken[(0, 2)] = 5;
+        broken[(8, 8)] = 12;
+        let expected = broken.clone().validate().unwrap_err();
+
+        let errors = broken.clone().validate_bounded::<16>().unwrap_err();
+        assert!(!errors.overflowed());
+        assert_eq!(errors.iter().cloned().collect::<Vec<_>>(), expected);
+
+        let errors = broken.validate_bounded::<2>().unwrap_err();
+        assert!(errors.overflowed());
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors.into_iter().cloned().collect::<Vec<_>>(),
+            expected[..2]
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{Sudoku, ValidationErrorType, EMPTY, SUDOKU_SIZE};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SetError {
+    OutOfBounds {
+        row: usize,
+        col: usize,
+    },
+    OutOfRange {
+        value: u8,
+    },
+    /// The value is already present in the unit at `index`
+    Conflict {
+        type_: ValidationErrorType,
+        index: (usize, usize),
+    },
+}
+
+impl Default for Sudoku {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Sudoku {
+    /// Grid with every cell empty
+    pub fn empty() -> Self {
+        Sudoku {
+            grid: [[EMPTY; SUDOKU_SIZE]; SUDOKU_SIZE],
+        }
+    }
+
+    /// Set a cell to `value` (or clear it with [`EMPTY`]) and return the previous value.
+    ///
+    /// Only the position and the range of `value` are checked, conflicts with
+    /// other cells are allowed, see [`Sudoku::find_conflict`] to reject them
+    pub fn try_set(&mut self, row: usize, col: usize, value: u8) -> Result<u8, SetError> {
+        if value as usize > SUDOKU_SIZE {
+            return Err(SetError::OutOfRange { value });
+        }
+        let cell = self
+            .grid
+            .get_mut(row)
+            .and_then(|values| values.get_mut(col))
+            .ok_or(SetError::OutOfBounds { row, col })?;
+
+        Ok(core::mem::replace(cell, value))
+    }
+
+    /// First cell sharing a row, column or box with `(row, col)` that already holds `value`
+    pub fn find_conflict(
+        &self,
+        row: usize,
+        col: usize,
+        value: u8,
+    ) -> Option<(ValidationErrorType, (usize, usize))> {
+        if value == EMPTY {
+            return None;
+        }
+
+        self.units()
+            .filter(|(_, cells)| cells.iter().any(|(index, _)| *index == (row, col)))
+            .find_map(|(type_, cells)| {
+                cells
+                    .iter()
+                    .find(|(index, other)| *index != (row, col) && *other == value)
+                    .map(|(index, _)| (type_, *index))
+            })
+    }
+}
+
+/// Cell-by-cell construction of a [`Sudoku`], every step is checked and a
+/// rejected one leaves the builder as it was
+///
+/// ```
+/// use sudoku_tester::{SetError, SudokuBuilder};
+///
+/// let mut builder = SudokuBuilder::new().reject_conflicts(true);
+/// builder.set(0, 0, 5)?.set(0, 1, 3)?;
+/// assert!(matches!(builder.set(4, 0, 5), Err(SetError::Conflict { .. })));
+/// assert_eq!(builder.build()[(0, 1)], 3);
+/// # Ok::<(), SetError>(())
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SudokuBuilder {
+    sudoku: Sudoku,
+    reject_conflicts: bool,
+}
+
+impl SudokuBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Continue from an existing grid
+    pub fn from_sudoku(sudoku: Sudoku) -> Self {
+        Self {
+            sudoku,
+            reject_conflicts: false,
+        }
+    }
+
+    /// When enabled, [`SudokuBuilder::set`] fails on values already present
+    /// in the same row, column or box
+    pub fn reject_conflicts(mut self, reject: bool) -> Self {
+        self.reject_conflicts = reject;
+        self
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: u8) -> Result<&mut Self, SetError> {
+        if self.reject_conflicts {
+            if let Some((type_, index)) = self.sudoku.find_conflict(row, col, value) {
+                return Err(SetError::Conflict { type_, index });
+            }
+        }
+        self.sudoku.try_set(row, col, value)?;
+        Ok(self)
+    }
+
+    pub fn build(self) -> Sudoku {
+        self.sudoku
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_set() {
+        let mut sudoku = Sudoku::empty();
+
+        assert_eq!(sudoku.try_set(1, 2, 7), Ok(EMPTY));
+        assert_eq!(sudoku.try_set(1, 2, 3), Ok(7));
+        assert_eq!(
+            sudoku.try_set(1, 2, 10),
+            Err(SetError::OutOfRange { value: 10 })
+        );
+        assert_eq!(
+            sudoku.try_set(9, 2, 1),
+            Err(SetError::OutOfBounds { row: 9, col: 2 })
+        );
+        assert_eq!(sudoku[(1, 2)], 3);
+    }
+
+    #[test]
+    fn test_builder_conflicts() {
+        let mut builder = SudokuBuilder::new();
+        builder.set(0, 0, 5).unwrap();
+
+        // Conflicts are allowed unless asked otherwise
+        assert!(builder.clone().set(0, 8, 5).is_ok());
+
+        let mut builder = builder.reject_conflicts(true);
+        assert_eq!(
+            builder.set(0, 8, 5).err(),
+            Some(SetError::Conflict {
+                type_: ValidationErrorType::Row(0),
+                index: (0, 0)
+            })
+        );
+        assert_eq!(
+            builder.set(2, 2, 5).err(),
+            Some(SetError::Conflict {
+                type_: ValidationErrorType::Box(0),
+                index: (0, 0)
+            })
+        );
+        assert_eq!(
+            builder.set(9, 0, 1).err(),
+            Some(SetError::OutOfBounds { row: 9, col: 0 })
+        );
+
+        // Rejected placements keep the earlier ones
+        builder.set(0, 0, 5).unwrap().set(1, 3, 5).unwrap();
+        let sudoku = builder.build();
+        assert_eq!(sudoku.cells().filter(|(_, value)| *value == 5).count(), 2);
+    }
+}
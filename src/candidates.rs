@@ -0,0 +1,140 @@
+//! Candidates, or pencil marks, of the empty cells of a grid
+//!
+//! Candidates are bitmasks where bit `d - 1` stands for digit `d`,
+//! see [`digits`] to list them
+use crate::{box_index, Sudoku, EMPTY, SUDOKU_SIZE};
+
+pub(crate) const ALL_CANDIDATES: u16 = (1 << SUDOKU_SIZE) - 1;
+
+/// Whether two different cells share a row, a column or a box
+pub(crate) fn sees(a: (usize, usize), b: (usize, usize)) -> bool {
+    a != b && (a.0 == b.0 || a.1 == b.1 || box_index(a.0, a.1) == box_index(b.0, b.1))
+}
+
+/// Digits of a candidate bitmask in ascending order
+pub fn digits(mask: u16) -> impl Iterator<Item = u8> {
+    (0..SUDOKU_SIZE as u8)
+        .filter(move |digit| mask & (1 << digit) != 0)
+        .map(|digit| digit + 1)
+}
+
+/// A grid with the candidates of each empty cell: the digits its row,
+/// column and box don't hold yet, minus the ones eliminated since
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CandidateGrid {
+    pub(crate) sudoku: Sudoku,
+    pub(crate) candidates: [[u16; SUDOKU_SIZE]; SUDOKU_SIZE],
+}
+
+impl CandidateGrid {
+    pub fn sudoku(&self) -> &Sudoku {
+        &self.sudoku
+    }
+
+    pub fn into_sudoku(self) -> Sudoku {
+        self.sudoku
+    }
+
+    /// Candidates of a cell, `0` for a filled cell and `None` outside of the grid
+    pub fn get(&self, row: usize, col: usize) -> Option<u16> {
+        self.candidates.get(row)?.get(col).copied()
+    }
+
+    pub fn contains(&self, (row, col): (usize, usize), digit: u8) -> bool {
+        (1..=SUDOKU_SIZE as u8).contains(&digit)
+            && self.get(row, col).unwrap_or(0) & (1 << (digit - 1)) != 0
+    }
+
+    /// Empty cells in row-major order with their candidates
+    pub fn empty_cells(&self) -> impl Iterator<Item = ((usize, usize), u16)> + '_ {
+        self.sudoku
+            .cells()
+            .filter(|(_, value)| *value == EMPTY)
+            .map(|((row, col), _)| ((row, col), self.candidates[row][col]))
+    }
+
+    /// Fill a cell and remove `value` from the candidates of its row, column
+    /// and box. Whether `value` was a candidate isn't checked.
+    ///
+    /// # Panics
+    /// If `value` isn't a digit from 1 to 9 or `position` is outside of the grid
+    pub fn place(&mut self, position: (usize, usize), value: u8) {
+        assert!(
+            (1..=SUDOKU_SIZE as u8).contains(&value),
+            "not a digit: {value}"
+        );
+        self.sudoku[position] = value;
+        self.candidates[position.0][position.1] = 0;
+        let bit = 1 << (value - 1);
+        for row in 0..SUDOKU_SIZE {
+            for col in 0..SUDOKU_SIZE {
+                if sees(position, (row, col)) {
+                    self.candidates[row][col] &= !bit;
+                }
+            }
+        }
+    }
+
+    /// Rule out `digit` for a cell, returns whether it was a candidate
+    pub fn eliminate(&mut self, position: (usize, usize), digit: u8) -> bool {
+        let found = self.contains(position, digit);
+        if found {
+            self.candidates[position.0][position.1] &= !(1 << (digit - 1));
+        }
+        found
+    }
+}
+
+impl Sudoku {
+    /// Candidates of every empty cell given the current rows, columns and boxes.
+    /// Out-of-range values count as filled cells without ruling anything out
+    pub fn candidates(&self) -> CandidateGrid {
+        let mut grid = CandidateGrid {
+            sudoku: self.clone(),
+            candidates: [[ALL_CANDIDATES; SUDOKU_SIZE]; SUDOKU_SIZE],
+        };
+        for (position, value) in self.cells() {
+            match value {
+                EMPTY => {}
+                1..=9 => grid.place(position, value),
+                _ => grid.candidates[position.0][position.1] = 0,
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_candidates() {
+        let mut grid = Sudoku::from_line(PUZZLE).unwrap().candidates();
+
+        assert_eq!(
+            digits(grid.get(0, 2).unwrap()).collect::<Vec<_>>(),
+            [1, 2, 4]
+        );
+        assert_eq!(grid.get(0, 0), Some(0));
+        assert_eq!(grid.get(0, 9), None);
+        assert_eq!(grid.empty_cells().count(), 51);
+        assert!(grid.contains((0, 2), 4));
+        assert!(!grid.contains((0, 2), 0));
+
+        grid.place((0, 2), 4);
+        assert_eq!(grid.sudoku()[(0, 2)], 4);
+        assert!(!grid.contains((0, 5), 4));
+        assert!(!grid.contains((1, 1), 4));
+        assert!(grid.contains((4, 1), 2));
+
+        assert!(grid.eliminate((4, 1), 2));
+        assert!(!grid.eliminate((4, 1), 2));
+        assert!(!grid.contains((4, 1), 2));
+        assert_eq!(grid.empty_cells().count(), 50);
+    }
+}
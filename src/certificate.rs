@@ -0,0 +1,210 @@
+//! Verifiable evidence that a puzzle has exactly one solution
+//!
+//! The certificate is the complete search tree of the puzzle: at every branch
+//! all candidates of one cell are tried, and each case ends either in the
+//! solution or in a cell without candidates. Anyone can replay it with
+//! [`UniquenessCertificate::verify`] without trusting the solver that built it
+use alloc::vec::Vec;
+use core::slice;
+
+use crate::{
+    solver::{Backtracker, CELLS_COUNT},
+    Sudoku, SUDOKU_SIZE,
+};
+
+/// Node of the search tree. The tree is stored flat, in depth-first order:
+/// a [`ProofStep::Branch`] is followed by the subtree of each of its cases
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProofStep {
+    /// Every cell is filled, the grid is the solution
+    Solved,
+    /// The empty cell has no candidate left, the branch is refuted
+    DeadEnd { position: (usize, usize) },
+    /// Every candidate of the empty cell is tried, in the order of `values`
+    Branch {
+        position: (usize, usize),
+        values: Vec<u8>,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UniquenessCertificate {
+    pub puzzle: Sudoku,
+    pub solution: Sudoku,
+    /// Search tree rooted at the puzzle, exactly one leaf is [`ProofStep::Solved`]
+    pub proof: Vec<ProofStep>,
+}
+
+fn position(index: usize) -> (usize, usize) {
+    (index / SUDOKU_SIZE, index % SUDOKU_SIZE)
+}
+
+/// Search tree of the current state, stops expanding once a second solution is found
+fn prove(state: &mut Backtracker, proof: &mut Vec<ProofStep>, solutions: &mut Vec<Sudoku>) {
+    let Some((index, mask)) = state.most_constrained() else {
+        solutions.push(state.to_sudoku());
+        proof.push(ProofStep::Solved);
+        return;
+    };
+    if mask == 0 {
+        proof.push(ProofStep::DeadEnd {
+            position: position(index),
+        });
+        return;
+    }
+
+    let values = (1..=SUDOKU_SIZE as u8)
+        .filter(|value| mask & (1 << (value - 1)) != 0)
+        .collect::<Vec<_>>();
+    proof.push(ProofStep::Branch {
+        position: position(index),
+        values: values.clone(),
+    });
+    for value in values {
+        if solutions.len() > 1 {
+            return;
+        }
+        state.place(index, value);
+        prove(state, proof, solutions);
+        state.remove(index);
+    }
+}
+
+/// Number of solved leaves of the next subtree, `None` if any step of the proof doesn't hold
+fn replay(
+    state: &mut Backtracker,
+    steps: &mut slice::Iter<ProofStep>,
+    solution: &Sudoku,
+) -> Option<usize> {
+    let index_of = |(row, col): (usize, usize)| {
+        Some(row * SUDOKU_SIZE + col).filter(|_| row < SUDOKU_SIZE && col < SUDOKU_SIZE)
+    };
+
+    match steps.next()? {
+        ProofStep::Solved => {
+            let solved = (0..CELLS_COUNT).all(|index| !state.is_empty(index));
+            (solved && state.to_sudoku() == *solution).then_some(1)
+        }
+        ProofStep::DeadEnd { position } => {
+            let index = index_of(*position)?;
+            (state.is_empty(index) && state.candidates(index) == 0).then_some(0)
+        }
+        ProofStep::Branch { position, values } => {
+            let index = index_of(*position)?;
+            let tried = values.iter().try_fold(0u16, |tried, value| {
+                let bit = 1u16.checked_shl(u32::from(*value).checked_sub(1)?)?;
+                Some(tried | bit).filter(|_| tried & bit == 0)
+            })?;
+            if !state.is_empty(index) || tried != state.candidates(index) {
+                return None;
+            }
+
+            values.iter().try_fold(0, |solved, value| {
+                state.place(index, *value);
+                let found = replay(state, steps, solution);
+                state.remove(index);
+                Some(solved + found?)
+            })
+        }
+    }
+}
+
+impl UniquenessCertificate {
+    /// Replay the proof from the puzzle: every branch must cover all candidates
+    /// of its cell, every dead end must really have none, and exactly one leaf
+    /// must be the solution
+    pub fn verify(&self) -> bool {
+        let Some(mut state) = Backtracker::new(&self.puzzle) else {
+            return false;
+        };
+        let mut steps = self.proof.iter();
+        replay(&mut state, &mut steps, &self.solution) == Some(1) && steps.next().is_none()
+    }
+}
+
+impl Sudoku {
+    /// Same as [`Sudoku::has_unique_solution`], but with a proof of it.
+    /// `None` if the puzzle has no solution or several
+    pub fn uniqueness_certificate(&self) -> Option<UniquenessCertificate> {
+        let mut state = Backtracker::new(self)?;
+        let mut proof = Vec::new();
+        let mut solutions = Vec::new();
+        prove(&mut state, &mut proof, &mut solutions);
+
+        let [solution] = <[Sudoku; 1]>::try_from(solutions).ok()?;
+        Some(UniquenessCertificate {
+            puzzle: self.clone(),
+            solution,
+            proof,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const HARD_PUZZLE: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+    #[test]
+    fn test_certificate() {
+        for line in [PUZZLE, HARD_PUZZLE] {
+            let puzzle = Sudoku::from_line(line).unwrap();
+            let certificate = puzzle.uniqueness_certificate().unwrap();
+            assert_eq!(Some(&certificate.solution), puzzle.solve().as_ref());
+            assert!(certificate.verify());
+        }
+    }
+
+    #[test]
+    fn test_certificate_rejected() {
+        let mut line = PUZZLE.to_owned();
+        line.replace_range(0..2, "..");
+        assert_eq!(
+            Sudoku::from_line(&line).unwrap().uniqueness_certificate(),
+            None
+        );
+
+        let puzzle = Sudoku::from_line(HARD_PUZZLE).unwrap();
+        let certificate = puzzle.uniqueness_certificate().unwrap();
+
+        let mut forged = certificate.clone();
+        forged.solution[(0, 1)] = 9;
+        assert!(!forged.verify());
+
+        // Dropping a case leaves a candidate unexplored
+        let mut forged = certificate.clone();
+        let values = forged
+            .proof
+            .iter_mut()
+            .find_map(|step| match step {
+                ProofStep::Branch { values, .. } if values.len() > 1 => Some(values),
+                _ => None,
+            })
+            .unwrap();
+        values.pop();
+        assert!(!forged.verify());
+
+        let mut forged = certificate.clone();
+        forged.proof.push(ProofStep::Solved);
+        assert!(!forged.verify());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_certificate_serde() {
+        let certificate = Sudoku::from_line(PUZZLE)
+            .unwrap()
+            .uniqueness_certificate()
+            .unwrap();
+        let json = serde_json::to_string(&certificate).unwrap();
+        let restored = serde_json::from_str::<UniquenessCertificate>(&json).unwrap();
+        assert_eq!(restored, certificate);
+        assert!(restored.verify());
+    }
+}
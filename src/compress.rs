@@ -0,0 +1,309 @@
+//! Compact encoding of complete solution grids.
+//!
+//! Cells are visited in row-major order, each one is stored as its rank among
+//! the digits still allowed by the cells before it, and all the ranks are packed
+//! into one mixed-radix number. Forced cells cost nothing, so a typical grid takes
+//! 10-11 bytes instead of 81, close to the ~73 bits needed to tell apart all the
+//! 6.67e21 valid grids
+//!
+//! Any other grid, puzzles included, packs into [`PACKED_LEN`] bytes with
+//! [`Sudoku::to_bytes`]: one 4-bit nibble per cell, high nibble first,
+//! `0` for an empty cell. Fixed size and no parsing make it the cheap choice
+//! for large stores
+use alloc::vec::Vec;
+
+use crate::{
+    box_index, candidates::ALL_CANDIDATES, solver::CELLS_COUNT, Sudoku, EMPTY, SUDOKU_SIZE,
+};
+
+/// Size of [`Sudoku::to_bytes`], the last low nibble is padding
+pub const PACKED_LEN: usize = CELLS_COUNT.div_ceil(2);
+/// Written by [`Sudoku::to_bytes`] for values that don't fit a cell
+const OUT_OF_RANGE: u8 = 0xF;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CompressionError {
+    /// Only complete and valid grids can be compressed
+    NotASolution,
+    /// The bytes don't come from [`Sudoku::compress_solution`]
+    Malformed,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UnpackError {
+    /// The input isn't [`PACKED_LEN`] bytes long
+    WrongLength { len: usize },
+    /// The nibble of the cell isn't `0..=9`
+    InvalidCell { index: (usize, usize), value: u8 },
+    /// The padding nibble at the end isn't zero
+    Padding,
+}
+
+/// Arbitrary precision unsigned integer, little-endian base 2^32 limbs.
+/// Only what the mixed-radix packing needs
+#[derive(Default)]
+struct BigUint(Vec<u32>);
+
+impl BigUint {
+    fn mul_add(&mut self, factor: u32, addend: u32) {
+        let mut carry = addend as u64;
+        for limb in &mut self.0 {
+            let value = *limb as u64 * factor as u64 + carry;
+            *limb = value as u32;
+            carry = value >> 32;
+        }
+        if carry > 0 {
+            self.0.push(carry as u32);
+        }
+    }
+
+    /// Divide in place, returning the remainder
+    fn div_rem(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0u64;
+        for limb in self.0.iter_mut().rev() {
+            let value = (remainder << 32) | *limb as u64;
+            *limb = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+        remainder as u32
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self
+            .0
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect::<Vec<_>>();
+        while bytes.last() == Some(&0) {
+            bytes.pop();
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let mut limbs = bytes
+            .chunks(4)
+            .map(|chunk| {
+                let mut limb = [0; 4];
+                limb[..chunk.len()].copy_from_slice(chunk);
+                u32::from_le_bytes(limb)
+            })
+            .collect::<Vec<_>>();
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self(limbs)
+    }
+}
+
+/// Digits already used in the row, column and box of each cell while
+/// the grid is being walked in row-major order
+struct Walk {
+    rows: [u16; SUDOKU_SIZE],
+    columns: [u16; SUDOKU_SIZE],
+    boxes: [u16; SUDOKU_SIZE],
+}
+
+impl Walk {
+    fn new() -> Self {
+        Self {
+            rows: [0; SUDOKU_SIZE],
+            columns: [0; SUDOKU_SIZE],
+            boxes: [0; SUDOKU_SIZE],
+        }
+    }
+
+    fn candidates(&self, row: usize, col: usize) -> u16 {
+        !(self.rows[row] | self.columns[col] | self.boxes[box_index(row, col)]) & ALL_CANDIDATES
+    }
+
+    fn place(&mut self, row: usize, col: usize, value: u8) {
+        let bit = 1 << (value - 1);
+        self.rows[row] |= bit;
+        self.columns[col] |= bit;
+        self.boxes[box_index(row, col)] |= bit;
+    }
+}
+
+impl Sudoku {
+    /// Pack a complete and valid grid into a few bytes,
+    /// see [`Sudoku::decompress_solution`] for the reverse
+    pub fn compress_solution(&self) -> Result<Vec<u8>, CompressionError> {
+        let mut walk = Walk::new();
+        let mut digits = Vec::with_capacity(SUDOKU_SIZE * SUDOKU_SIZE);
+
+        for ((row, col), value) in self.cells() {
+            let candidates = walk.candidates(row, col);
+            if value == EMPTY || value as usize > SUDOKU_SIZE {
+                return Err(CompressionError::NotASolution);
+            }
+            let bit = 1u16 << (value - 1);
+            if candidates & bit == 0 {
+                return Err(CompressionError::NotASolution);
+            }
+            digits.push((
+                (candidates & (bit - 1)).count_ones(),
+                candidates.count_ones(),
+            ));
+            walk.place(row, col, value);
+        }
+
+        let mut packed = BigUint::default();
+        digits
+            .into_iter()
+            .rev()
+            .filter(|(_, radix)| *radix > 1)
+            .for_each(|(digit, radix)| packed.mul_add(radix, digit));
+
+        Ok(packed.to_bytes())
+    }
+
+    pub fn decompress_solution(bytes: &[u8]) -> Result<Sudoku, CompressionError> {
+        let mut packed = BigUint::from_bytes(bytes);
+        let mut walk = Walk::new();
+        let mut sudoku = Sudoku::empty();
+
+        for index in 0..SUDOKU_SIZE * SUDOKU_SIZE {
+            let (row, col) = (index / SUDOKU_SIZE, index % SUDOKU_SIZE);
+            let mut candidates = walk.candidates(row, col);
+            if candidates == 0 {
+                return Err(CompressionError::Malformed);
+            }
+            let rank = match candidates.count_ones() {
+                1 => 0,
+                radix => packed.div_rem(radix),
+            };
+            (0..rank).for_each(|_| candidates &= candidates - 1);
+
+            let value = candidates.trailing_zeros() as u8 + 1;
+            sudoku.grid[row][col] = value;
+            walk.place(row, col, value);
+        }
+
+        match packed.0.is_empty() {
+            true => Ok(sudoku),
+            false => Err(CompressionError::Malformed),
+        }
+    }
+}
+
+impl Sudoku {
+    /// Pack the grid into [`PACKED_LEN`] bytes, 4 bits per cell.
+    ///
+    /// Values above 9 (see [`ValidationError::OutOfRange`](crate::ValidationError::OutOfRange))
+    /// are written as `0xF`, which [`Sudoku::from_bytes`] rejects
+    pub fn to_bytes(&self) -> [u8; PACKED_LEN] {
+        let mut bytes = [0; PACKED_LEN];
+        for (index, value) in self.grid.iter().flatten().enumerate() {
+            let nibble = match *value as usize <= SUDOKU_SIZE {
+                true => *value,
+                false => OUT_OF_RANGE,
+            };
+            bytes[index / 2] |= match index % 2 {
+                0 => nibble << 4,
+                _ => nibble,
+            };
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Sudoku, UnpackError> {
+        let bytes: &[u8; PACKED_LEN] = bytes
+            .try_into()
+            .map_err(|_| UnpackError::WrongLength { len: bytes.len() })?;
+        if bytes[PACKED_LEN - 1] & 0xF != 0 {
+            return Err(UnpackError::Padding);
+        }
+
+        let mut sudoku = Sudoku::empty();
+        for (index, cell) in sudoku.grid.iter_mut().flatten().enumerate() {
+            let value = match index % 2 {
+                0 => bytes[index / 2] >> 4,
+                _ => bytes[index / 2] & 0xF,
+            };
+            if value as usize > SUDOKU_SIZE {
+                let index = (index / SUDOKU_SIZE, index % SUDOKU_SIZE);
+                return Err(UnpackError::InvalidCell { index, value });
+            }
+            *cell = value;
+        }
+        Ok(sudoku)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::Transformation;
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_round_trip() {
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+
+        for seed in 0..16 {
+            let solution = Transformation::from_seed(seed).apply(&solution);
+            let compressed = solution.compress_solution().unwrap();
+
+            assert!(compressed.len() <= 12, "{} bytes", compressed.len());
+            assert_eq!(Sudoku::decompress_solution(&compressed), Ok(solution));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut sudoku = Sudoku::from_line(SOLUTION).unwrap();
+        sudoku[(0, 0)] = EMPTY;
+        assert_eq!(
+            sudoku.compress_solution(),
+            Err(CompressionError::NotASolution)
+        );
+        sudoku[(0, 0)] = 3;
+        assert_eq!(
+            sudoku.compress_solution(),
+            Err(CompressionError::NotASolution)
+        );
+
+        assert_eq!(
+            Sudoku::decompress_solution(&[0xFF; 64]),
+            Err(CompressionError::Malformed)
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        const PUZZLE: &str =
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let bytes = puzzle.to_bytes();
+
+        assert_eq!(bytes.len(), 41);
+        assert_eq!(bytes[..2], [0x53, 0x00]);
+        assert_eq!(Sudoku::from_bytes(&bytes), Ok(puzzle.clone()));
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+        assert_eq!(Sudoku::from_bytes(&solution.to_bytes()), Ok(solution));
+
+        assert_eq!(
+            Sudoku::from_bytes(&bytes[1..]),
+            Err(UnpackError::WrongLength { len: 40 })
+        );
+        let mut padded = bytes;
+        padded[PACKED_LEN - 1] |= 1;
+        assert_eq!(Sudoku::from_bytes(&padded), Err(UnpackError::Padding));
+
+        let mut out_of_range = puzzle;
+        out_of_range[(0, 1)] = 12;
+        assert_eq!(
+            Sudoku::from_bytes(&out_of_range.to_bytes()),
+            Err(UnpackError::InvalidCell {
+                index: (0, 1),
+                value: 0xF
+            })
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! Rules of the grid as pluggable constraints.
+//!
+//! Rows, columns and boxes are ordinary [`Constraint`]s too, so a variant is
+//! just a [`RuleSet`] with a few more of them
+//!
+//! ```
+//! use std::borrow::Cow;
+//!
+//! use sudoku_tester::{Constraint, RuleSet, Sudoku, ValidationError, EMPTY};
+//!
+//! /// Cells a chess knight's move apart can't hold the same digit
+//! struct AntiKnight;
+//!
+//! impl Constraint for AntiKnight {
+//!     fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+//!         let mut errors = vec![];
+//!         for ((row, col), value) in grid.cells().filter(|(_, value)| *value != EMPTY) {
+//!             for (other_row, other_col) in [(row + 1, col + 2), (row + 2, col + 1)]
+//!                 .into_iter()
+//!                 .chain(col.checked_sub(2).map(|col| (row + 1, col)))
+//!                 .chain(col.checked_sub(1).map(|col| (row + 2, col)))
+//!             {
+//!                 if grid.get(other_row, other_col) == Some(value) {
+//!                     errors.push(ValidationError::Violation {
+//!                         rule: Cow::Borrowed("anti-knight"),
+//!                         indexes: [(row, col), (other_row, other_col)].into_iter().collect(),
+//!                     });
+//!                 }
+//!             }
+//!         }
+//!         errors
+//!     }
+//! }
+//!
+//! let rules = RuleSet::standard().with(AntiKnight);
+//! let sudoku = Sudoku::from_line(
+//!     "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+//! )
+//! .unwrap();
+//! assert!(sudoku.validate_against(&rules).is_err());
+//! ```
+use alloc::{boxed::Box, vec::Vec};
+#[cfg(feature = "std")]
+use core::fmt;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::{unit_duplicates, Rules, Sudoku, ValidationError, ValidationErrorType};
+
+pub trait Constraint {
+    /// Every violation of the constraint found in the grid,
+    /// empty cells must not be reported as violations
+    fn check(&self, grid: &Sudoku) -> Vec<ValidationError>;
+
+    /// Label of the constraint in a [`CheckProfile`], the type name by default
+    fn name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+}
+
+/// Every row contains each digit at most once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rows;
+
+/// Every column contains each digit at most once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Columns;
+
+/// Every 3x3 box contains each digit at most once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Boxes;
+
+/// Both main diagonals contain each digit at most once, as in X-Sudoku
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Diagonals;
+
+impl Constraint for Rows {
+    fn name(&self) -> &'static str {
+        "rows"
+    }
+
+    fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+        grid.rows()
+            .enumerate()
+            .flat_map(|(i, cells)| unit_duplicates(ValidationErrorType::Row(i), cells))
+            .collect()
+    }
+}
+
+impl Constraint for Columns {
+    fn name(&self) -> &'static str {
+        "columns"
+    }
+
+    fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+        grid.columns()
+            .enumerate()
+            .flat_map(|(i, cells)| unit_duplicates(ValidationErrorType::Column(i), cells))
+            .collect()
+    }
+}
+
+impl Constraint for Boxes {
+    fn name(&self) -> &'static str {
+        "boxes"
+    }
+
+    fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+        grid.boxes()
+            .enumerate()
+            .flat_map(|(i, cells)| unit_duplicates(ValidationErrorType::Box(i), cells))
+            .collect()
+    }
+}
+
+impl Constraint for Diagonals {
+    fn name(&self) -> &'static str {
+        "diagonals"
+    }
+
+    fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+        grid.diagonals()
+            .flat_map(|(type_, cells)| unit_duplicates(type_, cells))
+            .collect()
+    }
+}
+
+/// The four shaded regions of Hyper-Sudoku contain each digit at most once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtraRegions;
+
+impl Constraint for ExtraRegions {
+    fn name(&self) -> &'static str {
+        "extra regions"
+    }
+
+    fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+        grid.extra_regions()
+            .flat_map(|(type_, cells)| unit_duplicates(type_, cells))
+            .collect()
+    }
+}
+
+/// Ordered collection of constraints, errors are reported in the order
+/// the constraints were added
+#[derive(Default)]
+pub struct RuleSet {
+    constraints: Vec<Box<dyn Constraint>>,
+}
+
+impl RuleSet {
+    /// Rule set without any constraint
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rows, columns and boxes
+    pub fn standard() -> Self {
+        Self::new().with(Rows).with(Columns).with(Boxes)
+    }
+
+    pub fn with(mut self, constraint: impl Constraint + 'static) -> Self {
+        self.push(constraint);
+        self
+    }
+
+    pub fn push(&mut self, constraint: impl Constraint + 'static) {
+        self.constraints.push(Box::new(constraint));
+    }
+
+    pub fn len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    pub fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+        self.constraints
+            .iter()
+            .flat_map(|constraint| constraint.check(grid))
+            .collect()
+    }
+
+    #[cfg(feature = "std")]
+    /// Same as [`RuleSet::check`], also measuring each constraint, to find
+    /// the rule that slows a live-checking loop down
+    pub fn check_profiled(&self, grid: &Sudoku) -> (Vec<ValidationError>, CheckProfile) {
+        let mut errors = vec![];
+        let timings = self
+            .constraints
+            .iter()
+            .map(|constraint| {
+                let start = Instant::now();
+                let found = constraint.check(grid);
+                let timing = ConstraintTiming {
+                    name: constraint.name(),
+                    elapsed: start.elapsed(),
+                    violations: found.len(),
+                };
+                errors.extend(found);
+                timing
+            })
+            .collect();
+
+        (errors, CheckProfile { timings })
+    }
+}
+
+#[cfg(feature = "std")]
+/// Cost of a single constraint during [`RuleSet::check_profiled`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstraintTiming {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    pub violations: usize,
+}
+
+#[cfg(feature = "std")]
+/// Per-constraint timings, in the order of the rule set.
+///
+/// `Display` renders one line per constraint
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckProfile {
+    pub timings: Vec<ConstraintTiming>,
+}
+
+#[cfg(feature = "std")]
+impl CheckProfile {
+    pub fn total(&self) -> Duration {
+        self.timings.iter().map(|timing| timing.elapsed).sum()
+    }
+
+    pub fn slowest(&self) -> Option<&ConstraintTiming> {
+        self.timings.iter().max_by_key(|timing| timing.elapsed)
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for CheckProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.timings.iter().try_for_each(|timing| {
+            writeln!(
+                f,
+                "{:<24} {:>12?} {:>6} violations",
+                timing.name, timing.elapsed, timing.violations
+            )
+        })?;
+        write!(f, "{:<24} {:>12?}", "total", self.total())
+    }
+}
+
+/// A rule set is a constraint too, so it can be nested or applied to linked grids
+impl Constraint for RuleSet {
+    fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+        RuleSet::check(self, grid)
+    }
+}
+
+impl From<Rules> for RuleSet {
+    fn from(rules: Rules) -> Self {
+        let mut rule_set = Self::standard();
+        if rules.diagonals {
+            rule_set.push(Diagonals);
+        }
+        if rules.extra_regions {
+            rule_set.push(ExtraRegions);
+        }
+        rule_set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_rule_set() {
+        let mut sudoku = Sudoku::from_line(SOLUTION).unwrap();
+        sudoku[(0, 0)] = 3;
+
+        assert!(RuleSet::new().check(&sudoku).is_empty());
+        assert_eq!(RuleSet::from(Rules::STANDARD).len(), 3);
+        assert_eq!(
+            RuleSet::new().with(Columns).check(&sudoku),
+            vec![ValidationError::Dublication {
+                type_: ValidationErrorType::Column(0),
+                value: 3,
+                indexes: [(0, 0), (8, 0)].into_iter().collect(),
+            }]
+        );
+        assert_eq!(
+            RuleSet::standard().check(&sudoku),
+            sudoku.clone().validate().unwrap_err()
+        );
+        assert_eq!(RuleSet::standard().check(&sudoku).len(), 3);
+    }
+
+    #[test]
+    fn test_large_violation() {
+        /// Reports the first two rows as one violation, as a long broken thermometer would
+        struct Thermo;
+
+        impl Constraint for Thermo {
+            fn check(&self, grid: &Sudoku) -> Vec<ValidationError> {
+                let cells = grid.cells().take(18).map(|(index, _)| index).collect();
+                vec![ValidationError::Violation {
+                    rule: "thermo".into(),
+                    indexes: cells,
+                }]
+            }
+        }
+
+        let errors = RuleSet::new()
+            .with(Thermo)
+            .check(&Sudoku::from_line(SOLUTION).unwrap());
+        let [ValidationError::Violation { indexes, .. }] = errors.as_slice() else {
+            panic!("unexpected errors: {errors:?}");
+        };
+        assert_eq!(indexes.len(), 18);
+        assert_eq!(indexes.last(), Some(&(1, 8)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_check_profiled() {
+        let mut sudoku = Sudoku::from_line(SOLUTION).unwrap();
+        sudoku[(0, 0)] = 3;
+        let rules = RuleSet::from(Rules::X_SUDOKU);
+
+        let (errors, profile) = rules.check_profiled(&sudoku);
+        assert_eq!(errors, rules.check(&sudoku));
+        assert_eq!(
+            profile
+                .timings
+                .iter()
+                .map(|timing| (timing.name, timing.violations))
+                .collect::<Vec<_>>(),
+            vec![("rows", 1), ("columns", 1), ("boxes", 1), ("diagonals", 4)]
+        );
+        assert!(profile.slowest().is_some());
+        assert_eq!(profile.to_string().lines().count(), 5);
+    }
+}
@@ -0,0 +1,122 @@
+//! Puzzle of the day: every client computing it for the same date and source
+//! gets the same puzzle, on any platform, without talking to each other
+use crate::{
+    generator::{self, GeneratorConfig},
+    rng::Rng,
+    Sudoku,
+};
+
+/// Calendar date of the proleptic Gregorian calendar
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// `None` if there's no such day, e.g. February 29 of a common year
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+
+    /// Days since 1970-01-01, negative before it
+    pub fn days_since_epoch(&self) -> i64 {
+        // Howard Hinnant's `days_from_civil`, years start in March so the
+        // leap day is the last day of the year
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let day_of_year =
+            (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+}
+
+/// Where the daily puzzle comes from
+#[derive(Debug, Clone, Copy)]
+pub enum DailySource<'a> {
+    /// Curated puzzles, served one per day in order and from the start again
+    /// once all of them were served
+    Corpus(&'a [Sudoku]),
+    /// A fresh puzzle each day, the seed of the config is mixed with the date
+    Generator(GeneratorConfig),
+}
+
+/// Puzzle for `date`, fully determined by `date` and `source`
+///
+/// # Panics
+/// If the corpus is empty
+pub fn daily_puzzle(date: Date, source: DailySource) -> Sudoku {
+    let days = date.days_since_epoch();
+    match source {
+        DailySource::Corpus(corpus) => {
+            assert!(!corpus.is_empty(), "the daily corpus is empty");
+            corpus[days.rem_euclid(corpus.len() as i64) as usize].clone()
+        }
+        DailySource::Generator(config) => generator::generate(&GeneratorConfig {
+            seed: Rng::new(config.seed ^ days as u64).next_u64(),
+            ..config
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date() {
+        let days = |year, month, day| Date::new(year, month, day).unwrap().days_since_epoch();
+
+        assert_eq!(days(1970, 1, 1), 0);
+        assert_eq!(days(1969, 12, 31), -1);
+        assert_eq!(days(2000, 3, 1), 11_017);
+        assert_eq!(days(2024, 2, 29) + 1, days(2024, 3, 1));
+        assert_eq!(Date::new(2023, 2, 29), None);
+        assert_eq!(Date::new(2100, 2, 29), None);
+        assert_eq!(Date::new(2024, 13, 1), None);
+        assert_eq!(Date::new(2024, 4, 31), None);
+    }
+
+    #[test]
+    fn test_daily_puzzle() {
+        let today = Date::new(2024, 5, 17).unwrap();
+        let tomorrow = Date::new(2024, 5, 18).unwrap();
+
+        let config = GeneratorConfig {
+            target_clues: 30,
+            ..GeneratorConfig::default()
+        };
+        let puzzle = daily_puzzle(today, DailySource::Generator(config));
+        assert_eq!(puzzle, daily_puzzle(today, DailySource::Generator(config)));
+        assert_ne!(
+            puzzle,
+            daily_puzzle(tomorrow, DailySource::Generator(config))
+        );
+        assert!(puzzle.has_unique_solution());
+
+        let corpus = [puzzle.clone(), puzzle.solve().unwrap()];
+        let served = daily_puzzle(today, DailySource::Corpus(&corpus));
+        assert_ne!(served, daily_puzzle(tomorrow, DailySource::Corpus(&corpus)));
+        assert_eq!(
+            served,
+            daily_puzzle(
+                Date::new(2024, 5, 19).unwrap(),
+                DailySource::Corpus(&corpus)
+            )
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{Sudoku, EMPTY, SUDOKU_SIZE};
+
+/// State of one cell when comparing a reference grid with another one
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CellDiff {
+    /// Empty in both grids
+    Empty,
+    /// Same value in both grids
+    Match(u8),
+    /// Both grids hold a value, but not the same one
+    Wrong { expected: u8, actual: u8 },
+    /// The reference has a value the other grid lacks
+    Missing { expected: u8 },
+    /// The other grid has a value where the reference is empty
+    Extra { actual: u8 },
+}
+
+/// Cell-by-cell comparison produced by [`Sudoku::diff`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GridDiff {
+    cells: [[CellDiff; SUDOKU_SIZE]; SUDOKU_SIZE],
+}
+
+impl GridDiff {
+    pub fn get(&self, row: usize, col: usize) -> Option<CellDiff> {
+        self.cells.get(row)?.get(col).copied()
+    }
+
+    /// All cells in row-major order
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), CellDiff)> + '_ {
+        self.cells.iter().enumerate().flat_map(|(row, cells)| {
+            cells
+                .iter()
+                .enumerate()
+                .map(move |(col, cell)| ((row, col), *cell))
+        })
+    }
+
+    /// Positions of the cells matching `predicate`
+    pub fn positions<'a>(
+        &'a self,
+        predicate: impl Fn(&CellDiff) -> bool + 'a,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.cells()
+            .filter(move |(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+
+    /// No wrong, missing or extra cell
+    pub fn is_identical(&self) -> bool {
+        self.cells()
+            .all(|(_, cell)| matches!(cell, CellDiff::Empty | CellDiff::Match(_)))
+    }
+}
+
+impl Sudoku {
+    /// Compare `other` against this grid taken as the reference,
+    /// e.g. a solution against a player's attempt
+    pub fn diff(&self, other: &Sudoku) -> GridDiff {
+        GridDiff {
+            cells: core::array::from_fn(|row| {
+                core::array::from_fn(|col| match (self.grid[row][col], other.grid[row][col]) {
+                    (EMPTY, EMPTY) => CellDiff::Empty,
+                    (expected, EMPTY) => CellDiff::Missing { expected },
+                    (EMPTY, actual) => CellDiff::Extra { actual },
+                    (expected, actual) if expected == actual => CellDiff::Match(expected),
+                    (expected, actual) => CellDiff::Wrong { expected, actual },
+                })
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let mut reference = Sudoku::empty();
+        reference[(0, 0)] = 1;
+        reference[(0, 1)] = 2;
+        reference[(0, 2)] = 3;
+        let mut attempt = Sudoku::empty();
+        attempt[(0, 0)] = 1;
+        attempt[(0, 1)] = 4;
+        attempt[(1, 0)] = 5;
+
+        let diff = reference.diff(&attempt);
+        assert_eq!(diff.get(0, 0), Some(CellDiff::Match(1)));
+        assert_eq!(
+            diff.get(0, 1),
+            Some(CellDiff::Wrong {
+                expected: 2,
+                actual: 4
+            })
+        );
+        assert_eq!(diff.get(0, 2), Some(CellDiff::Missing { expected: 3 }));
+        assert_eq!(diff.get(1, 0), Some(CellDiff::Extra { actual: 5 }));
+        assert_eq!(diff.get(8, 8), Some(CellDiff::Empty));
+        assert_eq!(diff.get(9, 8), None);
+        assert_eq!(
+            diff.positions(|cell| matches!(cell, CellDiff::Wrong { .. }))
+                .collect::<Vec<_>>(),
+            vec![(0, 1)]
+        );
+        assert!(!diff.is_identical());
+        assert!(reference.diff(&reference).is_identical());
+    }
+}
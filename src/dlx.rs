@@ -0,0 +1,256 @@
+//! Algorithm X on dancing links over the [`ExactCover`] encoding: every
+//! step picks the constraint with the fewest placements left, which makes
+//! counting and enumerating hard or ambiguous puzzles faster
+use alloc::{vec, vec::Vec};
+use core::ops::ControlFlow;
+
+use crate::{
+    encode::{to_exact_cover, ExactCover, COLUMNS_COUNT},
+    solver::Solver,
+    Sudoku, SUDOKU_SIZE,
+};
+
+/// Toroidal doubly linked lists of the cover matrix, stored as indexes.
+/// Node `0` is the root, nodes `1..=COLUMNS_COUNT` head the columns
+/// and the data nodes follow
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// Header of the column of each node
+    column: Vec<usize>,
+    /// Index of the matrix row of each data node
+    row: Vec<usize>,
+    /// Number of nodes left in each column, by header
+    size: Vec<usize>,
+}
+
+impl Links {
+    fn new(cover: &ExactCover) -> Self {
+        let headers = COLUMNS_COUNT + 1;
+        let mut links = Self {
+            left: (0..headers)
+                .map(|i| (i + COLUMNS_COUNT) % headers)
+                .collect(),
+            right: (0..headers).map(|i| (i + 1) % headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+        };
+
+        for (index, (_, _, columns)) in cover.rows.iter().enumerate() {
+            let first = links.left.len();
+            for (offset, column) in columns.iter().enumerate() {
+                let header = column + 1;
+                let node = first + offset;
+                links.column.push(header);
+                links.row.push(index);
+                links.up.push(links.up[header]);
+                links.down.push(header);
+                let last = links.up[header];
+                links.down[last] = node;
+                links.up[header] = node;
+                links.size[header] += 1;
+
+                links.left.push(match offset {
+                    0 => first + columns.len() - 1,
+                    _ => node - 1,
+                });
+                links.right.push(match offset + 1 == columns.len() {
+                    true => first,
+                    false => node + 1,
+                });
+            }
+        }
+        links
+    }
+
+    fn cover(&mut self, header: usize) {
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+
+        let mut i = self.down[header];
+        while i != header {
+            let mut j = self.right[i];
+            while j != i {
+                let (up, down) = (self.up[j], self.down[j]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[j]] -= 1;
+                j = self.right[j];
+            }
+            i = self.down[i];
+        }
+    }
+
+    fn uncover(&mut self, header: usize) {
+        let mut i = self.up[header];
+        while i != header {
+            let mut j = self.left[i];
+            while j != i {
+                self.size[self.column[j]] += 1;
+                let (up, down) = (self.up[j], self.down[j]);
+                self.down[up] = j;
+                self.up[down] = j;
+                j = self.left[j];
+            }
+            i = self.up[i];
+        }
+
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+    }
+
+    /// Uncovered column with the fewest nodes, `None` once all are covered
+    fn smallest_column(&self) -> Option<usize> {
+        let mut best = None;
+        let mut header = self.right[0];
+        while header != 0 {
+            if best.is_none_or(|best| self.size[header] < self.size[best]) {
+                best = Some(header);
+            }
+            header = self.right[header];
+        }
+        best
+    }
+
+    /// Depth-first search calling `on_solution` with the selected rows of
+    /// every exact cover, stops as soon as the callback breaks
+    fn search<B, F>(&mut self, selected: &mut Vec<usize>, on_solution: &mut F) -> ControlFlow<B>
+    where
+        F: FnMut(&[usize]) -> ControlFlow<B>,
+    {
+        let Some(header) = self.smallest_column() else {
+            return on_solution(selected);
+        };
+        if self.size[header] == 0 {
+            return ControlFlow::Continue(());
+        }
+
+        self.cover(header);
+        let mut node = self.down[header];
+        let mut flow = ControlFlow::Continue(());
+        while node != header && flow.is_continue() {
+            selected.push(self.row[node]);
+            let mut j = self.right[node];
+            while j != node {
+                self.cover(self.column[j]);
+                j = self.right[j];
+            }
+
+            flow = self.search(selected, on_solution);
+
+            let mut j = self.left[node];
+            while j != node {
+                self.uncover(self.column[j]);
+                j = self.left[j];
+            }
+            selected.pop();
+            node = self.down[node];
+        }
+        self.uncover(header);
+        flow
+    }
+}
+
+/// Dancing links backend, see [`SolverBackend::Dlx`](crate::solver::SolverBackend::Dlx)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DancingLinks;
+
+impl DancingLinks {
+    /// Run the search unless a value is out of range, `to_exact_cover`
+    /// would take such a cell as empty
+    fn search<B>(
+        sudoku: &Sudoku,
+        on_solution: &mut impl FnMut(&ExactCover, &[usize]) -> ControlFlow<B>,
+    ) -> Option<ControlFlow<B>> {
+        if sudoku
+            .cells()
+            .any(|(_, value)| value as usize > SUDOKU_SIZE)
+        {
+            return None;
+        }
+        let cover = to_exact_cover(sudoku);
+        let mut selected = Vec::with_capacity(SUDOKU_SIZE * SUDOKU_SIZE);
+        Some(Links::new(&cover).search(&mut selected, &mut |rows| on_solution(&cover, rows)))
+    }
+}
+
+impl Solver for DancingLinks {
+    fn solve(&self, sudoku: &Sudoku) -> Option<Sudoku> {
+        Self::search(sudoku, &mut |cover, rows| {
+            ControlFlow::Break(cover.decode(rows).ok())
+        })?
+        .break_value()
+        .flatten()
+    }
+
+    fn count_solutions(&self, sudoku: &Sudoku, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+        let mut count = 0;
+        let _ = Self::search(sudoku, &mut |_, _| {
+            count += 1;
+            match count < limit {
+                true => ControlFlow::Continue(()),
+                false => ControlFlow::Break(()),
+            }
+        });
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{Backtracking, SolverBackend};
+    use alloc::{borrow::ToOwned, string::String};
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const HARD_PUZZLE: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+    #[test]
+    fn test_dlx_matches_backtracking() {
+        for line in [PUZZLE, HARD_PUZZLE] {
+            let sudoku = Sudoku::from_line(line).unwrap();
+            let solution = DancingLinks.solve(&sudoku).unwrap();
+            assert_eq!(Some(&solution), Backtracking.solve(&sudoku).as_ref());
+            assert!(solution.validate().is_ok());
+            assert!(SolverBackend::Dlx.has_unique_solution(&sudoku));
+        }
+    }
+
+    #[test]
+    fn test_dlx_count_solutions() {
+        let mut line = PUZZLE.to_owned();
+        line.replace_range(0..2, "..");
+        let sudoku = Sudoku::from_line(&line).unwrap();
+        assert_eq!(DancingLinks.count_solutions(&sudoku, usize::MAX), 2);
+        assert_eq!(DancingLinks.count_solutions(&sudoku, 1), 1);
+        assert_eq!(DancingLinks.count_solutions(&sudoku, 0), 0);
+
+        let empty = Sudoku::from_line(&".".repeat(81)).unwrap();
+        assert_eq!(DancingLinks.count_solutions(&empty, 1000), 1000);
+    }
+
+    #[test]
+    fn test_dlx_unsolvable() {
+        let mut line = String::from(PUZZLE);
+        line.replace_range(1..2, "5");
+        let contradiction = Sudoku::from_line(&line).unwrap();
+        assert_eq!(DancingLinks.solve(&contradiction), None);
+        assert_eq!(DancingLinks.count_solutions(&contradiction, 10), 0);
+
+        let mut out_of_range = Sudoku::from_line(PUZZLE).unwrap();
+        out_of_range[(0, 2)] = 12;
+        assert_eq!(DancingLinks.solve(&out_of_range), None);
+    }
+}
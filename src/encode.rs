@@ -0,0 +1,284 @@
+//! Sudoku as input for general-purpose solvers: a SAT formula in DIMACS CNF or
+//! an exact cover matrix, with decoders mapping their solutions back to a grid
+//!
+//! ```
+//! use sudoku_tester::{encode, Sudoku};
+//!
+//! let puzzle = Sudoku::from_line(
+//!     "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+//! )
+//! .unwrap();
+//! let dimacs = encode::to_cnf(&puzzle).to_string();
+//! assert!(dimacs.contains("p cnf 729 "));
+//!
+//! // ... run a SAT solver on `dimacs`, then read its model back
+//! # let model = encode::model_of(&puzzle.solve().unwrap());
+//! assert_eq!(encode::decode_model(&model), Ok(puzzle.solve().unwrap()));
+//! ```
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{box_index, solver::CELLS_COUNT, Sudoku, EMPTY, SUDOKU_SIZE};
+
+/// One boolean variable per cell and digit
+pub const VARIABLES_COUNT: usize = CELLS_COUNT * SUDOKU_SIZE;
+/// One exact cover column per cell, and per digit in every row, column and box
+pub const COLUMNS_COUNT: usize = CELLS_COUNT * 4;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DecodeError {
+    /// Not a variable of the formula, or not a row of the matrix
+    Unknown(i64),
+    /// Several digits chosen for the cell
+    Conflict { position: (usize, usize) },
+    /// No digit chosen for the cell
+    Missing { position: (usize, usize) },
+}
+
+/// DIMACS variable saying that the cell at `row`, `col` holds `digit`, `1..=729`
+pub fn variable(row: usize, col: usize, digit: u8) -> i32 {
+    (row * CELLS_COUNT + col * SUDOKU_SIZE + digit as usize) as i32
+}
+
+fn cell_of(variable: usize) -> ((usize, usize), u8) {
+    let index = variable - 1;
+    (
+        (index / CELLS_COUNT, index / SUDOKU_SIZE % SUDOKU_SIZE),
+        (index % SUDOKU_SIZE) as u8 + 1,
+    )
+}
+
+/// Formula in conjunctive normal form, `Display` writes it in the DIMACS format
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cnf {
+    /// Each clause is a disjunction of literals: a variable, negative when negated
+    pub clauses: Vec<Vec<i32>>,
+}
+
+impl fmt::Display for Cnf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "c sudoku, variable 81 * row + 9 * column + digit")?;
+        writeln!(f, "p cnf {VARIABLES_COUNT} {}", self.clauses.len())?;
+        self.clauses.iter().try_for_each(|clause| {
+            clause
+                .iter()
+                .try_for_each(|literal| write!(f, "{literal} "))?;
+            writeln!(f, "0")
+        })
+    }
+}
+
+/// The 27 houses as lists of cells
+fn houses() -> impl Iterator<Item = [(usize, usize); SUDOKU_SIZE]> {
+    (0..SUDOKU_SIZE).flat_map(|i| {
+        [
+            core::array::from_fn(|j| (i, j)),
+            core::array::from_fn(|j| (j, i)),
+            core::array::from_fn(|j| (i / 3 * 3 + j / 3, i % 3 * 3 + j % 3)),
+        ]
+    })
+}
+
+/// "Exactly one of" as clauses: one clause for at least one, pairs for at most one
+fn exactly_one(clauses: &mut Vec<Vec<i32>>, variables: [i32; SUDOKU_SIZE]) {
+    clauses.push(variables.to_vec());
+    for (i, first) in variables.iter().enumerate() {
+        for second in &variables[i + 1..] {
+            clauses.push(vec![-first, -second]);
+        }
+    }
+}
+
+/// Every cell holds exactly one digit, every house holds each digit exactly
+/// once, and each given is a unit clause
+pub fn to_cnf(sudoku: &Sudoku) -> Cnf {
+    let mut clauses = Vec::new();
+    for ((row, col), _) in sudoku.cells() {
+        exactly_one(
+            &mut clauses,
+            core::array::from_fn(|digit| variable(row, col, digit as u8 + 1)),
+        );
+    }
+    for house in houses() {
+        for digit in 1..=SUDOKU_SIZE as u8 {
+            exactly_one(
+                &mut clauses,
+                house.map(|(row, col)| variable(row, col, digit)),
+            );
+        }
+    }
+    sudoku
+        .cells()
+        .filter(|(_, value)| (1..=SUDOKU_SIZE as u8).contains(value))
+        .for_each(|((row, col), value)| clauses.push(vec![variable(row, col, value)]));
+
+    Cnf { clauses }
+}
+
+/// Decoded grid, unless a cell was left out
+fn complete(sudoku: Sudoku) -> Result<Sudoku, DecodeError> {
+    let missing = sudoku.cells().find(|(_, value)| *value == EMPTY);
+    match missing {
+        Some((position, _)) => Err(DecodeError::Missing { position }),
+        None => Ok(sudoku),
+    }
+}
+
+/// Model of the formula satisfied by a complete grid, as a SAT solver would report it
+pub fn model_of(solution: &Sudoku) -> Vec<i32> {
+    (1..=VARIABLES_COUNT)
+        .map(|variable| {
+            let ((row, col), digit) = cell_of(variable);
+            match solution[(row, col)] == digit {
+                true => variable as i32,
+                false => -(variable as i32),
+            }
+        })
+        .collect()
+}
+
+/// Grid of a satisfying assignment: the literals of the model, negated for
+/// false variables. Terminating zeros are skipped, false variables may be left out
+pub fn decode_model(model: &[i32]) -> Result<Sudoku, DecodeError> {
+    let mut sudoku = Sudoku::empty();
+    for literal in model.iter().filter(|literal| **literal > 0) {
+        let variable = *literal as usize;
+        if variable > VARIABLES_COUNT {
+            return Err(DecodeError::Unknown(i64::from(*literal)));
+        }
+        let (position, digit) = cell_of(variable);
+        if sudoku[position] != EMPTY {
+            return Err(DecodeError::Conflict { position });
+        }
+        sudoku[position] = digit;
+    }
+    complete(sudoku)
+}
+
+/// Exact cover matrix: choosing a set of rows covering every one of the
+/// [`COLUMNS_COUNT`] columns exactly once solves the puzzle
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExactCover {
+    /// One row per possible placement: the cell, the digit and the covered
+    /// columns. Givens have only the row of their digit
+    pub rows: Vec<((usize, usize), u8, [usize; 4])>,
+}
+
+/// Columns covered by placing `digit` at `row`, `col`: the cell, then the digit
+/// in the row, in the column and in the box
+fn cover_columns(row: usize, col: usize, digit: u8) -> [usize; 4] {
+    let digit = digit as usize - 1;
+    [
+        row * SUDOKU_SIZE + col,
+        CELLS_COUNT + row * SUDOKU_SIZE + digit,
+        2 * CELLS_COUNT + col * SUDOKU_SIZE + digit,
+        3 * CELLS_COUNT + box_index(row, col) * SUDOKU_SIZE + digit,
+    ]
+}
+
+pub fn to_exact_cover(sudoku: &Sudoku) -> ExactCover {
+    let rows = sudoku
+        .cells()
+        .flat_map(|((row, col), value)| {
+            let digits = match value {
+                1..=9 => value..=value,
+                _ => 1..=SUDOKU_SIZE as u8,
+            };
+            digits.map(move |digit| ((row, col), digit, cover_columns(row, col, digit)))
+        })
+        .collect();
+    ExactCover { rows }
+}
+
+impl ExactCover {
+    /// Grid of a solution given as indexes of the chosen rows
+    pub fn decode(&self, selected: &[usize]) -> Result<Sudoku, DecodeError> {
+        let mut sudoku = Sudoku::empty();
+        for index in selected {
+            let (position, digit, _) = self
+                .rows
+                .get(*index)
+                .ok_or(DecodeError::Unknown(*index as i64))?;
+            if sudoku[*position] != EMPTY {
+                return Err(DecodeError::Conflict {
+                    position: *position,
+                });
+            }
+            sudoku[*position] = *digit;
+        }
+        complete(sudoku)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_cnf() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let solution = puzzle.solve().unwrap();
+        let cnf = to_cnf(&puzzle);
+
+        // 4 * 81 "exactly one" constraints of 1 + 36 clauses, plus 30 givens
+        assert_eq!(cnf.clauses.len(), 4 * 81 * 37 + 30);
+        assert_eq!(variable(0, 0, 1), 1);
+        assert_eq!(variable(8, 8, 9), 729);
+
+        let model = model_of(&solution);
+        let satisfied = |literal: &i32| model[literal.unsigned_abs() as usize - 1] == *literal;
+        assert!(cnf
+            .clauses
+            .iter()
+            .all(|clause| clause.iter().any(satisfied)));
+        assert_eq!(decode_model(&model), Ok(solution.clone()));
+
+        let mut wrong = model_of(&solution);
+        wrong[1] = 2;
+        assert_eq!(
+            decode_model(&wrong),
+            Err(DecodeError::Conflict { position: (0, 0) })
+        );
+        assert_eq!(
+            decode_model(&model[..720]),
+            Err(DecodeError::Missing { position: (8, 8) })
+        );
+        assert_eq!(decode_model(&[730]), Err(DecodeError::Unknown(730)));
+
+        let dimacs = cnf.to_string();
+        assert_eq!(dimacs.lines().nth(1), Some("p cnf 729 12018"));
+        assert_eq!(dimacs.lines().last(), Some("729 0"));
+    }
+
+    #[test]
+    fn test_exact_cover() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let solution = puzzle.solve().unwrap();
+        let cover = to_exact_cover(&puzzle);
+        assert_eq!(cover.rows.len(), 30 + 51 * 9);
+
+        let selected = cover
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, (position, digit, _))| solution[*position] == *digit)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let mut covered = [0; COLUMNS_COUNT];
+        selected
+            .iter()
+            .flat_map(|index| cover.rows[*index].2)
+            .for_each(|column| covered[column] += 1);
+        assert!(covered.iter().all(|count| *count == 1));
+
+        assert_eq!(cover.decode(&selected), Ok(solution));
+        assert_eq!(
+            cover.decode(&selected[1..]),
+            Err(DecodeError::Missing { position: (0, 0) })
+        );
+        assert_eq!(cover.decode(&[10_000]), Err(DecodeError::Unknown(10_000)));
+    }
+}
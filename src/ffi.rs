@@ -0,0 +1,180 @@
+//! C ABI (`ffi` feature), see `include/sudoku_tester.h` for the C declarations.
+//! The shared library comes from the `bindings` crate of the workspace
+//!
+//! Grids are opaque heap objects owned by the caller: every grid returned
+//! through an out pointer must be released with [`sudoku_free`]
+use std::{
+    ffi::{c_char, CStr},
+    ptr,
+};
+
+use crate::{RuleSet, Sudoku, SUDOKU_SIZE};
+
+/// Result of every fallible call, `Ok` is always `0`
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SudokuStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidUtf8 = 2,
+    ParseError = 3,
+    /// The grid breaks a rule of sudoku
+    Invalid = 4,
+    Unsolvable = 5,
+}
+
+/// Either the one-line format or the 9-line grid
+fn parse(input: &str) -> Option<Sudoku> {
+    let input = input.trim();
+    match input.lines().count() {
+        1 => Sudoku::from_line(input).ok(),
+        _ => input.parse().ok(),
+    }
+}
+
+/// Parse a NUL-terminated grid and store a new grid in `*out`
+///
+/// # Safety
+/// `input` must be a valid NUL-terminated string and `out` a valid pointer to write to
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_parse(input: *const c_char, out: *mut *mut Sudoku) -> SudokuStatus {
+    if input.is_null() || out.is_null() {
+        return SudokuStatus::NullPointer;
+    }
+    let Ok(input) = CStr::from_ptr(input).to_str() else {
+        return SudokuStatus::InvalidUtf8;
+    };
+    match parse(input) {
+        Some(sudoku) => {
+            *out = Box::into_raw(Box::new(sudoku));
+            SudokuStatus::Ok
+        }
+        None => SudokuStatus::ParseError,
+    }
+}
+
+/// Check the grid for duplicates, `Ok` if there are none. Empty cells are
+/// allowed, as in the `validate` JavaScript binding. The number of errors
+/// found is written to `*error_count` unless it is null
+///
+/// # Safety
+/// `sudoku` must come from this library and not be freed yet,
+/// `error_count` must be null or a valid pointer to write to
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_validate(
+    sudoku: *const Sudoku,
+    error_count: *mut usize,
+) -> SudokuStatus {
+    let Some(sudoku) = sudoku.as_ref() else {
+        return SudokuStatus::NullPointer;
+    };
+    let count = RuleSet::standard().check(sudoku).len();
+    if !error_count.is_null() {
+        *error_count = count;
+    }
+    match count {
+        0 => SudokuStatus::Ok,
+        _ => SudokuStatus::Invalid,
+    }
+}
+
+/// Solve the grid and store the solution as a new grid in `*out`
+///
+/// # Safety
+/// `sudoku` must come from this library and not be freed yet,
+/// `out` must be a valid pointer to write to
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_solve(
+    sudoku: *const Sudoku,
+    out: *mut *mut Sudoku,
+) -> SudokuStatus {
+    let Some(sudoku) = sudoku.as_ref() else {
+        return SudokuStatus::NullPointer;
+    };
+    if out.is_null() {
+        return SudokuStatus::NullPointer;
+    }
+    match sudoku.solve() {
+        Some(solution) => {
+            *out = Box::into_raw(Box::new(solution));
+            SudokuStatus::Ok
+        }
+        None => SudokuStatus::Unsolvable,
+    }
+}
+
+/// Write the grid in the one-line format: 81 characters and a NUL terminator
+///
+/// # Safety
+/// `sudoku` must come from this library and not be freed yet,
+/// `buffer` must have room for at least 82 bytes
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_write_line(
+    sudoku: *const Sudoku,
+    buffer: *mut c_char,
+) -> SudokuStatus {
+    let Some(sudoku) = sudoku.as_ref() else {
+        return SudokuStatus::NullPointer;
+    };
+    if buffer.is_null() {
+        return SudokuStatus::NullPointer;
+    }
+    let line = sudoku.to_line();
+    ptr::copy_nonoverlapping(line.as_ptr().cast(), buffer, SUDOKU_SIZE * SUDOKU_SIZE);
+    *buffer.add(SUDOKU_SIZE * SUDOKU_SIZE) = 0;
+    SudokuStatus::Ok
+}
+
+/// Release a grid, null is ignored
+///
+/// # Safety
+/// `sudoku` must be null or come from this library and not be freed yet
+#[no_mangle]
+pub unsafe extern "C" fn sudoku_free(sudoku: *mut Sudoku) {
+    if !sudoku.is_null() {
+        drop(Box::from_raw(sudoku));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ffi() {
+        let input =
+            c"53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let mut puzzle = ptr::null_mut();
+        let mut solution = ptr::null_mut();
+        let mut errors = usize::MAX;
+        let mut buffer = [0 as c_char; 82];
+
+        unsafe {
+            assert_eq!(sudoku_parse(input.as_ptr(), &mut puzzle), SudokuStatus::Ok);
+            assert_eq!(sudoku_validate(puzzle, &mut errors), SudokuStatus::Ok);
+            assert_eq!(errors, 0);
+            assert_eq!(sudoku_solve(puzzle, &mut solution), SudokuStatus::Ok);
+            assert_eq!(
+                sudoku_write_line(solution, buffer.as_mut_ptr()),
+                SudokuStatus::Ok
+            );
+            assert_eq!(
+                CStr::from_ptr(buffer.as_ptr()).to_str(),
+                Ok("534678912672195348198342567859761423426853791713924856961537284287419635345286179")
+            );
+
+            assert_eq!(
+                sudoku_parse(c"12x".as_ptr(), &mut puzzle),
+                SudokuStatus::ParseError
+            );
+            assert_eq!(
+                sudoku_validate(ptr::null(), ptr::null_mut()),
+                SudokuStatus::NullPointer
+            );
+
+            sudoku_free(puzzle);
+            sudoku_free(solution);
+            sudoku_free(ptr::null_mut());
+        }
+    }
+}
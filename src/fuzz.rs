@@ -0,0 +1,171 @@
+//! Grid generators for property testing and fuzzing of downstream crates
+//!
+//! [`Sudoku`] itself implements `arbitrary::Arbitrary` (`arbitrary` feature) and
+//! `proptest::arbitrary::Arbitrary` (`proptest` feature) as a random grid,
+//! mostly invalid. Valid grids come from [`SolvedGrid`] and [`Puzzle`] with
+//! `arbitrary`, and from the [`strategy`] functions with `proptest`.
+//!
+//! Solved grids are random [`Transformation`]s of one solution: they cover only
+//! a fraction of all solved grids, which is plenty to exercise a solver or renderer
+use crate::{solver::CELLS_COUNT, transform::Transformation, Sudoku, SUDOKU_SIZE};
+
+const SOLUTION: Sudoku = Sudoku {
+    grid: [
+        [5, 3, 4, 6, 7, 8, 9, 1, 2],
+        [6, 7, 2, 1, 9, 5, 3, 4, 8],
+        [1, 9, 8, 3, 4, 2, 5, 6, 7],
+        [8, 5, 9, 7, 6, 1, 4, 2, 3],
+        [4, 2, 6, 8, 5, 3, 7, 9, 1],
+        [7, 1, 3, 9, 2, 4, 8, 5, 6],
+        [9, 6, 1, 5, 3, 7, 2, 8, 4],
+        [2, 8, 7, 4, 1, 9, 6, 3, 5],
+        [3, 4, 5, 2, 8, 6, 1, 7, 9],
+    ],
+};
+
+/// Solved grid picked by `seed`
+fn solved_from_seed(seed: u64) -> Sudoku {
+    Transformation::from_seed(seed).apply(&SOLUTION)
+}
+
+/// Empty every cell of `solution` whose index isn't in `clues`
+fn keep_clues(solution: &Sudoku, clues: impl IntoIterator<Item = usize>) -> Sudoku {
+    let mut puzzle = Sudoku::empty();
+    for index in clues {
+        let position = (index / SUDOKU_SIZE, index % SUDOKU_SIZE);
+        puzzle[position] = solution[position];
+    }
+    puzzle
+}
+
+/// A valid, completely filled grid
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SolvedGrid(pub Sudoku);
+
+/// A solved grid with some of its cells emptied: free of duplicates and
+/// solvable, though not necessarily with a unique solution
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Puzzle(pub Sudoku);
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Sudoku {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut sudoku = Sudoku::empty();
+        for cell in sudoku.grid.iter_mut().flatten() {
+            *cell = u.int_in_range(crate::EMPTY..=SUDOKU_SIZE as u8)?;
+        }
+        Ok(sudoku)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for SolvedGrid {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(solved_from_seed(u.arbitrary()?)))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Puzzle {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let SolvedGrid(solution) = u.arbitrary()?;
+        let mut clues = [false; CELLS_COUNT];
+        for clue in &mut clues {
+            *clue = u.arbitrary()?;
+        }
+        let clues = (0..CELLS_COUNT).filter(|index| clues[*index]);
+        Ok(Self(keep_clues(&solution, clues)))
+    }
+}
+
+/// Proptest strategies for the three kinds of grids
+#[cfg(feature = "proptest")]
+pub mod strategy {
+    use proptest::prelude::*;
+
+    use super::{keep_clues, solved_from_seed, CELLS_COUNT};
+    use crate::{Sudoku, EMPTY, SUDOKU_SIZE};
+
+    /// Any value of `0..=9` in every cell
+    pub fn random_grid() -> impl Strategy<Value = Sudoku> {
+        proptest::array::uniform9(proptest::array::uniform9(EMPTY..=SUDOKU_SIZE as u8))
+            .prop_map(|grid| Sudoku { grid })
+    }
+
+    /// A valid, completely filled grid
+    pub fn solved_grid() -> impl Strategy<Value = Sudoku> {
+        any::<u64>().prop_map(solved_from_seed)
+    }
+
+    /// A solved grid with exactly `clues` cells left, see [`super::Puzzle`]
+    ///
+    /// # Panics
+    /// If `clues` is more than 81
+    pub fn puzzle(clues: usize) -> impl Strategy<Value = Sudoku> {
+        assert!(clues <= CELLS_COUNT, "a grid has only {CELLS_COUNT} cells");
+        (
+            solved_grid(),
+            proptest::sample::subsequence((0..CELLS_COUNT).collect::<Vec<_>>(), clues),
+        )
+            .prop_map(|(solution, clues)| keep_clues(&solution, clues))
+    }
+
+    impl Arbitrary for Sudoku {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Sudoku>;
+
+        fn arbitrary_with(_: ()) -> Self::Strategy {
+            random_grid().boxed()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn test_solved_grid(sudoku in solved_grid()) {
+                prop_assert!(sudoku.cells().all(|(_, value)| value != EMPTY));
+                prop_assert!(sudoku.validate().is_ok());
+            }
+
+            #[test]
+            fn test_puzzle(sudoku in puzzle(30)) {
+                prop_assert_eq!(sudoku.cells().filter(|(_, value)| *value != EMPTY).count(), 30);
+                prop_assert!(sudoku.solve().is_some());
+            }
+
+            #[test]
+            fn test_random_grid(sudoku in any::<Sudoku>()) {
+                prop_assert!(sudoku.cells().all(|(_, value)| value as usize <= SUDOKU_SIZE));
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "arbitrary"))]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use super::*;
+    use crate::EMPTY;
+
+    #[test]
+    fn test_arbitrary() {
+        let data = (0..=255).cycle().take(1024).collect::<Vec<u8>>();
+        let mut u = Unstructured::new(&data);
+
+        let SolvedGrid(solution) = SolvedGrid::arbitrary(&mut u).unwrap();
+        assert!(solution.clone().validate().is_ok());
+        assert!(solution.cells().all(|(_, value)| value != EMPTY));
+
+        let Puzzle(puzzle) = Puzzle::arbitrary(&mut u).unwrap();
+        assert!(puzzle.solve().is_some());
+
+        let sudoku = Sudoku::arbitrary(&mut u).unwrap();
+        assert!(sudoku
+            .cells()
+            .all(|(_, value)| value as usize <= SUDOKU_SIZE));
+    }
+}
@@ -0,0 +1,873 @@
+//! Building blocks for interactive games on top of a puzzle
+use std::{
+    fmt,
+    io::{self, BufRead, Write},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    box_index, unit_duplicates, House, ReportDiff, Sudoku, ValidationReport, EMPTY, SUDOKU_SIZE,
+};
+
+/// Format version written by [`Snapshot::save`], bumped on every incompatible change
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Pencil marks of every cell, bit `d - 1` set when digit `d` is marked
+pub type PencilMarks = [[u16; SUDOKU_SIZE]; SUDOKU_SIZE];
+
+/// One action of the player, with enough information to be undone
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Move {
+    /// Also clears the pencil marks of the cell, `marks` holds them for undo
+    Place {
+        position: (usize, usize),
+        value: u8,
+        previous: u8,
+        marks: u16,
+    },
+    Erase {
+        position: (usize, usize),
+        previous: u8,
+    },
+    /// Toggles the pencil mark of `digit`
+    Pencil { position: (usize, usize), digit: u8 },
+}
+
+/// Complete state of a game in progress, to save and restore it later
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Snapshot {
+    pub puzzle: Sudoku,
+    /// Values entered by the player, givens of the puzzle are left empty here
+    pub entries: Sudoku,
+    pub pencil_marks: PencilMarks,
+    /// Moves from the oldest to the newest
+    pub history: Vec<Move>,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    UnsupportedVersion(String),
+    /// Line number (from 1) that couldn't be understood
+    Malformed {
+        line: usize,
+    },
+    /// Entry over a given of the puzzle, or out of range
+    InvalidEntry {
+        position: (usize, usize),
+    },
+    /// Move of the history (counted from 0) out of the grid, with a digit out
+    /// of range or on a given of the puzzle
+    InvalidMove {
+        index: usize,
+    },
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+fn write_move<W: Write>(writer: &mut W, move_: &Move) -> io::Result<()> {
+    match *move_ {
+        Move::Place {
+            position: (row, col),
+            value,
+            previous,
+            marks,
+        } => writeln!(writer, "place {row} {col} {value} {previous} {marks:03x}"),
+        Move::Erase {
+            position: (row, col),
+            previous,
+        } => writeln!(writer, "erase {row} {col} {previous}"),
+        Move::Pencil {
+            position: (row, col),
+            digit,
+        } => writeln!(writer, "pencil {row} {col} {digit}"),
+    }
+}
+
+fn parse_move(line: &str) -> Option<Move> {
+    let mut words = line.split(' ');
+    let kind = words.next()?;
+    let mut words = words.collect::<Vec<_>>();
+    // Only a placement ends with the pencil marks it cleared
+    let marks = match kind {
+        "place" => u16::from_str_radix(words.pop()?, 16)
+            .ok()
+            .filter(|marks| *marks < 1 << SUDOKU_SIZE)?,
+        _ => 0,
+    };
+    let numbers = words
+        .into_iter()
+        .map(|word| word.parse::<u8>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let digit = |value: u8| (value as usize <= SUDOKU_SIZE).then_some(value);
+
+    let move_ = match (kind, numbers.as_slice()) {
+        ("place", &[row, col, value, previous]) => Move::Place {
+            position: (row as usize, col as usize),
+            value: digit(value).filter(|value| *value != EMPTY)?,
+            previous: digit(previous)?,
+            marks,
+        },
+        ("erase", &[row, col, previous]) => Move::Erase {
+            position: (row as usize, col as usize),
+            previous: digit(previous)?,
+        },
+        ("pencil", &[row, col, value]) => Move::Pencil {
+            position: (row as usize, col as usize),
+            digit: digit(value).filter(|value| *value != EMPTY)?,
+        },
+        _ => return None,
+    };
+
+    let (Move::Place { position, .. }
+    | Move::Erase { position, .. }
+    | Move::Pencil { position, .. }) = move_;
+    (position.0 < SUDOKU_SIZE && position.1 < SUDOKU_SIZE).then_some(move_)
+}
+
+/// A move the session could have played on `puzzle`
+fn is_playable(puzzle: &Sudoku, move_: &Move) -> bool {
+    let digit = |value: u8| (1..=SUDOKU_SIZE as u8).contains(&value);
+    let previous = |value: u8| value == EMPTY || digit(value);
+    let (position, valid) = match *move_ {
+        Move::Place {
+            position,
+            value,
+            previous: before,
+            marks,
+        } => (
+            position,
+            digit(value) && previous(before) && marks < 1 << SUDOKU_SIZE,
+        ),
+        Move::Erase {
+            position,
+            previous: before,
+        } => (position, previous(before)),
+        Move::Pencil { position, digit: d } => (position, digit(d)),
+    };
+    valid && puzzle.get(position.0, position.1) == Some(EMPTY)
+}
+
+impl Snapshot {
+    /// Check what [`Snapshot::load`] checks, for snapshots built by hand:
+    /// entries only on empty cells of the puzzle and in range, and a history
+    /// of moves the session could have played
+    pub fn check(&self) -> Result<(), SnapshotError> {
+        if let Some((position, _)) = self.entries.cells().find(|&(position, value)| {
+            value != EMPTY && (value as usize > SUDOKU_SIZE || self.puzzle[position] != EMPTY)
+        }) {
+            return Err(SnapshotError::InvalidEntry { position });
+        }
+        match self
+            .history
+            .iter()
+            .position(|move_| !is_playable(&self.puzzle, move_))
+        {
+            Some(index) => Err(SnapshotError::InvalidMove { index }),
+            None => Ok(()),
+        }
+    }
+
+    /// Write as versioned text:
+    ///
+    /// ```text
+    /// sudoku-snapshot v1
+    /// puzzle <one-line grid>
+    /// entries <one-line grid>
+    /// pencil <81 cells, 3 hex digits each>
+    /// place <row> <col> <value> <previous> <cleared pencil marks, 3 hex digits>
+    /// erase <row> <col> <previous>
+    /// pencil <row> <col> <digit>
+    /// ...
+    /// ```
+    pub fn save<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "sudoku-snapshot v{SNAPSHOT_VERSION}")?;
+        writeln!(writer, "puzzle {}", self.puzzle.to_line())?;
+        writeln!(writer, "entries {}", self.entries.to_line())?;
+        write!(writer, "pencil ")?;
+        self.pencil_marks
+            .iter()
+            .flatten()
+            .try_for_each(|marks| write!(writer, "{marks:03x}"))?;
+        writeln!(writer)?;
+        self.history
+            .iter()
+            .try_for_each(|move_| write_move(&mut writer, move_))?;
+        writer.flush()
+    }
+
+    pub fn load<R: BufRead>(reader: R) -> Result<Self, SnapshotError> {
+        let mut lines = reader.lines().enumerate();
+        let mut next = |prefix: &str| -> Result<(usize, String), SnapshotError> {
+            let (index, line) = lines.next().ok_or(SnapshotError::Malformed { line: 0 })?;
+            let line = line?;
+            let rest = line
+                .strip_prefix(prefix)
+                .ok_or(SnapshotError::Malformed { line: index + 1 })?;
+            Ok((index + 1, rest.to_owned()))
+        };
+
+        let (_, version) = next("sudoku-snapshot ")?;
+        if version != format!("v{SNAPSHOT_VERSION}") {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        let (line, puzzle) = next("puzzle ")?;
+        let puzzle = Sudoku::from_line(&puzzle).map_err(|_| SnapshotError::Malformed { line })?;
+        let (line, entries) = next("entries ")?;
+        let entries = Sudoku::from_line(&entries)
+            .ok()
+            .filter(|entries| {
+                entries
+                    .cells()
+                    .all(|(position, value)| value == EMPTY || puzzle[position] == EMPTY)
+            })
+            .ok_or(SnapshotError::Malformed { line })?;
+
+        let (line, marks) = next("pencil ")?;
+        let mut pencil_marks = [[0; SUDOKU_SIZE]; SUDOKU_SIZE];
+        if marks.len() != 3 * SUDOKU_SIZE * SUDOKU_SIZE || !marks.is_ascii() {
+            return Err(SnapshotError::Malformed { line });
+        }
+        for (cell, hex) in pencil_marks
+            .iter_mut()
+            .flatten()
+            .zip(marks.as_bytes().chunks(3))
+        {
+            *cell = core::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u16::from_str_radix(hex, 16).ok())
+                .filter(|marks| *marks < 1 << SUDOKU_SIZE)
+                .ok_or(SnapshotError::Malformed { line })?;
+        }
+
+        let history = lines
+            .map(|(index, line)| {
+                parse_move(&line?)
+                    .filter(|move_| is_playable(&puzzle, move_))
+                    .ok_or(SnapshotError::Malformed { line: index + 1 })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            puzzle,
+            entries,
+            pencil_marks,
+            history,
+        })
+    }
+}
+
+/// Why a move was rejected by [`Session`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MoveError {
+    OutOfGrid,
+    /// Digits go from 1 to 9
+    InvalidDigit,
+    /// Givens of the puzzle can't be changed
+    Given,
+    /// The digit is already in the row, column or box, at this position
+    Conflict {
+        position: (usize, usize),
+    },
+}
+
+/// Counters of a [`Session`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Statistics {
+    /// Time since the session was created
+    pub elapsed: Duration,
+    /// Moves played, undone ones included
+    pub moves: usize,
+    pub undos: usize,
+    /// Moves refused with a [`MoveError`]
+    pub rejected: usize,
+}
+
+/// Value change of one cell, passed to the [`Session::on_change`] callbacks
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CellChange {
+    pub position: (usize, usize),
+    pub old: u8,
+    pub new: u8,
+    /// Duplicates the change fixed or introduced. Only the row, column and box
+    /// of the cell are checked again, the rest of the grid can't be affected
+    pub delta: ReportDiff,
+}
+
+type Observer = Box<dyn FnMut(&CellChange)>;
+
+/// A game in progress: the player's entries over a puzzle, with undo and redo
+pub struct Session {
+    puzzle: Sudoku,
+    entries: Sudoku,
+    pencil_marks: PencilMarks,
+    history: Vec<Move>,
+    /// Undone moves, the next one to redo last
+    undone: Vec<Move>,
+    started: Instant,
+    moves: usize,
+    undos: usize,
+    rejected: usize,
+    observers: Vec<Observer>,
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("puzzle", &self.puzzle)
+            .field("entries", &self.entries)
+            .field("history", &self.history)
+            .field("undone", &self.undone)
+            .field("observers", &self.observers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Row, column and box through a cell
+fn houses_of((row, col): (usize, usize)) -> [House; 3] {
+    [
+        House::Row(row),
+        House::Column(col),
+        House::Box(box_index(row, col)),
+    ]
+}
+
+impl Session {
+    pub fn new(puzzle: Sudoku) -> Self {
+        Self::resume(Snapshot {
+            puzzle,
+            entries: Sudoku::empty(),
+            pencil_marks: [[0; SUDOKU_SIZE]; SUDOKU_SIZE],
+            history: Vec::new(),
+        })
+    }
+
+    /// Resume a saved game, the statistics start over. The snapshot is
+    /// [checked](Snapshot::check) first, undoing an invalid move could
+    /// overwrite a given or leave the grid
+    pub fn from_snapshot(snapshot: Snapshot) -> Result<Self, SnapshotError> {
+        snapshot.check()?;
+        Ok(Self::resume(snapshot))
+    }
+
+    fn resume(snapshot: Snapshot) -> Self {
+        Self {
+            puzzle: snapshot.puzzle,
+            entries: snapshot.entries,
+            pencil_marks: snapshot.pencil_marks,
+            history: snapshot.history,
+            undone: Vec::new(),
+            started: Instant::now(),
+            moves: 0,
+            undos: 0,
+            rejected: 0,
+            observers: Vec::new(),
+        }
+    }
+
+    /// Call `callback` after every change of a cell value, whether from a move,
+    /// an undo or a redo. Pencil marks and moves keeping the value don't notify
+    pub fn on_change(&mut self, callback: impl FnMut(&CellChange) + 'static) {
+        self.observers.push(Box::new(callback));
+    }
+
+    /// State to save, undone moves are not part of it
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            puzzle: self.puzzle.clone(),
+            entries: self.entries.clone(),
+            pencil_marks: self.pencil_marks,
+            history: self.history.clone(),
+        }
+    }
+
+    pub fn puzzle(&self) -> &Sudoku {
+        &self.puzzle
+    }
+
+    /// Givens and entries together, as the player sees them
+    pub fn grid(&self) -> Sudoku {
+        let mut grid = self.puzzle.clone();
+        self.entries
+            .cells()
+            .filter(|(_, value)| *value != EMPTY)
+            .for_each(|(position, value)| grid[position] = value);
+        grid
+    }
+
+    pub fn pencil_marks(&self) -> &PencilMarks {
+        &self.pencil_marks
+    }
+
+    /// Moves from the oldest to the newest, undone ones excluded
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// Every cell filled without any duplicate
+    pub fn is_solved(&self) -> bool {
+        let grid = self.grid();
+        grid.cells().all(|(_, value)| value != EMPTY) && grid.validate().is_ok()
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        Statistics {
+            elapsed: self.started.elapsed(),
+            moves: self.moves,
+            undos: self.undos,
+            rejected: self.rejected,
+        }
+    }
+
+    /// Fill a cell, replacing the previous entry if any and clearing its pencil marks
+    pub fn place(&mut self, position: (usize, usize), value: u8) -> Result<(), MoveError> {
+        let checked = self.editable(position).and_then(|previous| {
+            if !(1..=SUDOKU_SIZE as u8).contains(&value) {
+                return Err(MoveError::InvalidDigit);
+            }
+            match self.conflict(position, value) {
+                Some(position) => Err(MoveError::Conflict { position }),
+                None => Ok(previous),
+            }
+        });
+        let previous = self.count(checked)?;
+        self.play(Move::Place {
+            position,
+            value,
+            previous,
+            marks: self.pencil_marks[position.0][position.1],
+        });
+        Ok(())
+    }
+
+    /// Clear the entry of a cell, erasing an empty cell is still a move
+    pub fn erase(&mut self, position: (usize, usize)) -> Result<(), MoveError> {
+        let previous = self.count(self.editable(position))?;
+        self.play(Move::Erase { position, previous });
+        Ok(())
+    }
+
+    /// Toggle the pencil mark of `digit`, allowed on filled cells too
+    pub fn toggle_pencil(&mut self, position: (usize, usize), digit: u8) -> Result<(), MoveError> {
+        let checked =
+            self.editable(position)
+                .and_then(|_| match (1..=SUDOKU_SIZE as u8).contains(&digit) {
+                    true => Ok(()),
+                    false => Err(MoveError::InvalidDigit),
+                });
+        self.count(checked)?;
+        self.play(Move::Pencil { position, digit });
+        Ok(())
+    }
+
+    /// Revert the newest move, returns it or `None` if there's nothing to undo
+    pub fn undo(&mut self) -> Option<Move> {
+        let move_ = self.history.pop()?;
+        match move_ {
+            Move::Place {
+                position,
+                previous,
+                marks,
+                ..
+            } => {
+                self.set_entry(position, previous);
+                self.pencil_marks[position.0][position.1] = marks;
+            }
+            Move::Erase { position, previous } => self.set_entry(position, previous),
+            Move::Pencil { position, digit } => self.toggle_mark(position, digit),
+        }
+        self.undone.push(move_);
+        self.undos += 1;
+        Some(move_)
+    }
+
+    /// Play again the last undone move. Any new move clears what can be redone
+    pub fn redo(&mut self) -> Option<Move> {
+        let move_ = self.undone.pop()?;
+        self.apply(move_);
+        self.history.push(move_);
+        Some(move_)
+    }
+
+    /// Current entry of a cell that isn't a given
+    fn editable(&self, position: (usize, usize)) -> Result<u8, MoveError> {
+        match self.puzzle.get(position.0, position.1) {
+            None => Err(MoveError::OutOfGrid),
+            Some(EMPTY) => Ok(self.entries[position]),
+            Some(_) => Err(MoveError::Given),
+        }
+    }
+
+    /// Another cell of the same row, column or box already holding `value`
+    fn conflict(&self, cell: (usize, usize), value: u8) -> Option<(usize, usize)> {
+        let grid = self.grid();
+        houses_of(cell)
+            .into_iter()
+            .flat_map(|house| grid.cells_in(house))
+            .find(|&(position, other)| position != cell && other == value)
+            .map(|(position, _)| position)
+    }
+
+    /// Duplicates within the houses of `position`
+    fn local_report(&self, position: (usize, usize)) -> ValidationReport {
+        let grid = self.grid();
+        ValidationReport::new(
+            houses_of(position)
+                .into_iter()
+                .flat_map(|house| unit_duplicates(house.into(), grid.cells_in(house)))
+                .collect(),
+        )
+    }
+
+    fn set_entry(&mut self, position: (usize, usize), value: u8) {
+        let old = self.entries[position];
+        if old == value {
+            return;
+        }
+        if self.observers.is_empty() {
+            self.entries[position] = value;
+            return;
+        }
+
+        let before = self.local_report(position);
+        self.entries[position] = value;
+        let change = CellChange {
+            position,
+            old,
+            new: value,
+            delta: before.diff(&self.local_report(position)),
+        };
+        self.observers
+            .iter_mut()
+            .for_each(|observer| observer(&change));
+    }
+
+    fn count<T>(&mut self, checked: Result<T, MoveError>) -> Result<T, MoveError> {
+        if checked.is_err() {
+            self.rejected += 1;
+        }
+        checked
+    }
+
+    fn play(&mut self, move_: Move) {
+        self.apply(move_);
+        self.history.push(move_);
+        self.undone.clear();
+        self.moves += 1;
+    }
+
+    fn apply(&mut self, move_: Move) {
+        match move_ {
+            Move::Place {
+                position, value, ..
+            } => {
+                self.set_entry(position, value);
+                self.pencil_marks[position.0][position.1] = 0;
+            }
+            Move::Erase { position, .. } => self.set_entry(position, EMPTY),
+            Move::Pencil { position, digit } => self.toggle_mark(position, digit),
+        }
+    }
+
+    fn toggle_mark(&mut self, (row, col): (usize, usize), digit: u8) {
+        self.pencil_marks[row][col] ^= 1 << (digit - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValidationError;
+    use std::{cell::RefCell, rc::Rc};
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    fn snapshot() -> Snapshot {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let mut entries = Sudoku::empty();
+        entries[(0, 2)] = 4;
+        let mut pencil_marks = [[0; SUDOKU_SIZE]; SUDOKU_SIZE];
+        pencil_marks[0][3] = 0b1_0010_0000;
+
+        Snapshot {
+            puzzle,
+            entries,
+            pencil_marks,
+            history: vec![
+                Move::Place {
+                    position: (0, 2),
+                    value: 1,
+                    previous: EMPTY,
+                    marks: 0,
+                },
+                Move::Place {
+                    position: (0, 2),
+                    value: 4,
+                    previous: 1,
+                    marks: 0,
+                },
+                Move::Pencil {
+                    position: (0, 3),
+                    digit: 6,
+                },
+                Move::Pencil {
+                    position: (0, 3),
+                    digit: 9,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let snapshot = snapshot();
+        let mut saved = Vec::new();
+        snapshot.save(&mut saved).unwrap();
+
+        assert_eq!(Snapshot::load(saved.as_slice()).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let mut saved = Vec::new();
+        snapshot().save(&mut saved).unwrap();
+        let saved = String::from_utf8(saved).unwrap();
+
+        assert!(matches!(
+            Snapshot::load(saved.replace("v1", "v0").as_bytes()),
+            Err(SnapshotError::UnsupportedVersion(version)) if version == "v0"
+        ));
+        assert!(matches!(
+            Snapshot::load(saved.replace("place 0 2 4 1", "place 0 9 4 1").as_bytes()),
+            Err(SnapshotError::Malformed { line: 6 })
+        ));
+        // An entry can't overwrite a given
+        assert!(matches!(
+            Snapshot::load(saved.replace("entries ..4", "entries 5.4").as_bytes()),
+            Err(SnapshotError::Malformed { line: 3 })
+        ));
+        // Neither can a move, undoing it would
+        assert!(matches!(
+            Snapshot::load(saved.replace("pencil 0 3 6", "pencil 0 0 6").as_bytes()),
+            Err(SnapshotError::Malformed { line: 7 })
+        ));
+    }
+
+    #[test]
+    fn test_from_snapshot_errors() {
+        let invalid = |history: Move| {
+            let mut snapshot = snapshot();
+            snapshot.history.push(history);
+            Session::from_snapshot(snapshot).err()
+        };
+        let index = snapshot().history.len();
+
+        for move_ in [
+            Move::Pencil {
+                position: (0, 3),
+                digit: 0,
+            },
+            Move::Erase {
+                position: (0, 0),
+                previous: EMPTY,
+            },
+            Move::Place {
+                position: (9, 0),
+                value: 1,
+                previous: EMPTY,
+                marks: 0,
+            },
+            Move::Place {
+                position: (0, 2),
+                value: 1,
+                previous: 12,
+                marks: 0,
+            },
+        ] {
+            assert!(matches!(
+                invalid(move_),
+                Some(SnapshotError::InvalidMove { index: i }) if i == index
+            ));
+        }
+
+        let mut snapshot = snapshot();
+        snapshot.entries[(0, 0)] = 1;
+        assert!(matches!(
+            Session::from_snapshot(snapshot),
+            Err(SnapshotError::InvalidEntry { position: (0, 0) })
+        ));
+    }
+
+    #[test]
+    fn test_session_moves() {
+        let mut session = Session::new(Sudoku::from_line(PUZZLE).unwrap());
+
+        assert_eq!(session.place((0, 0), 1), Err(MoveError::Given));
+        assert_eq!(session.place((0, 9), 1), Err(MoveError::OutOfGrid));
+        assert_eq!(session.place((0, 2), 10), Err(MoveError::InvalidDigit));
+        assert_eq!(
+            session.place((0, 2), 5),
+            Err(MoveError::Conflict { position: (0, 0) })
+        );
+        assert_eq!(
+            session.toggle_pencil((0, 2), 0),
+            Err(MoveError::InvalidDigit)
+        );
+
+        session.place((0, 2), 1).unwrap();
+        session.place((0, 2), 4).unwrap();
+        session.toggle_pencil((0, 3), 6).unwrap();
+        assert_eq!(session.grid()[(0, 2)], 4);
+        assert_eq!(session.pencil_marks()[0][3], 0b10_0000);
+        // Replacing its own value isn't a conflict
+        session.place((0, 2), 4).unwrap();
+        session.erase((0, 2)).unwrap();
+        assert_eq!(session.grid()[(0, 2)], EMPTY);
+
+        let statistics = session.statistics();
+        assert_eq!(statistics.moves, 5);
+        assert_eq!(statistics.rejected, 5);
+        assert!(!session.is_solved());
+    }
+
+    #[test]
+    fn test_session_undo_redo() {
+        let mut session = Session::new(Sudoku::from_line(PUZZLE).unwrap());
+        assert_eq!(session.undo(), None);
+
+        session.place((0, 2), 1).unwrap();
+        session.place((0, 2), 4).unwrap();
+        session.toggle_pencil((0, 3), 6).unwrap();
+
+        assert!(matches!(session.undo(), Some(Move::Pencil { .. })));
+        assert_eq!(session.pencil_marks()[0][3], 0);
+        assert_eq!(
+            session.undo(),
+            Some(Move::Place {
+                position: (0, 2),
+                value: 4,
+                previous: 1,
+                marks: 0,
+            })
+        );
+        assert_eq!(session.grid()[(0, 2)], 1);
+
+        assert!(session.redo().is_some());
+        assert_eq!(session.grid()[(0, 2)], 4);
+        assert_eq!(session.history().len(), 2);
+
+        // A new move drops the pencil mark left to redo
+        session.erase((0, 2)).unwrap();
+        assert_eq!(session.redo(), None);
+        assert_eq!(session.statistics().undos, 2);
+
+        let resumed = Session::from_snapshot(session.snapshot()).unwrap();
+        assert_eq!(resumed.grid(), session.grid());
+        assert_eq!(resumed.history(), session.history());
+    }
+
+    #[test]
+    fn test_session_place_clears_marks() {
+        let mut session = Session::new(Sudoku::from_line(PUZZLE).unwrap());
+        session.toggle_pencil((0, 2), 1).unwrap();
+        session.toggle_pencil((0, 2), 4).unwrap();
+
+        session.place((0, 2), 4).unwrap();
+        assert_eq!(session.pencil_marks()[0][2], 0);
+        session.undo();
+        assert_eq!(session.pencil_marks()[0][2], 0b1001);
+        session.redo();
+        assert_eq!(session.pencil_marks()[0][2], 0);
+
+        let mut saved = Vec::new();
+        session.snapshot().save(&mut saved).unwrap();
+        let snapshot = Snapshot::load(saved.as_slice()).unwrap();
+        assert_eq!(snapshot.history, session.history());
+        assert!(String::from_utf8(saved)
+            .unwrap()
+            .contains("place 0 2 4 0 009"));
+    }
+
+    #[test]
+    fn test_session_solved() {
+        const SOLUTION: &str =
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+        let mut session = Session::new(Sudoku::from_line(PUZZLE).unwrap());
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+
+        for (position, value) in solution.cells() {
+            if session.puzzle()[position] == EMPTY {
+                session.place(position, value).unwrap();
+            }
+        }
+        assert!(session.is_solved());
+    }
+
+    #[test]
+    fn test_session_on_change() {
+        let mut session = Session::new(Sudoku::from_line(PUZZLE).unwrap());
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&changes);
+        session.on_change(move |change| recorded.borrow_mut().push(change.clone()));
+
+        session.place((0, 2), 4).unwrap();
+        session.toggle_pencil((0, 3), 6).unwrap();
+        session.erase((0, 2)).unwrap();
+        // (0, 5) can take a 4 only while (0, 2) is empty
+        session.place((0, 5), 4).unwrap();
+        session.undo();
+        session.undo();
+        session.erase((0, 3)).unwrap();
+        assert_eq!(session.grid()[(0, 2)], 4);
+
+        let changes = changes.borrow();
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (change.position, change.old, change.new))
+                .collect::<Vec<_>>(),
+            vec![
+                ((0, 2), EMPTY, 4),
+                ((0, 2), 4, EMPTY),
+                ((0, 5), EMPTY, 4),
+                ((0, 5), 4, EMPTY),
+                ((0, 2), EMPTY, 4),
+            ]
+        );
+        assert!(changes.iter().all(|change| change.delta.is_empty()));
+    }
+
+    #[test]
+    fn test_session_change_delta() {
+        // A saved game can hold entries the session would have refused
+        let mut snapshot = snapshot();
+        snapshot.entries[(0, 2)] = 5;
+        snapshot.history.clear();
+        let mut session = Session::from_snapshot(snapshot).unwrap();
+
+        let deltas = Rc::new(RefCell::new(Vec::new()));
+        let recorded = Rc::clone(&deltas);
+        session.on_change(move |change| recorded.borrow_mut().push(change.delta.clone()));
+
+        session.erase((0, 2)).unwrap();
+        session.undo();
+
+        let deltas = deltas.borrow();
+        assert!(deltas[0].appeared.is_empty());
+        assert_eq!(
+            deltas[0]
+                .disappeared
+                .iter()
+                .map(ValidationError::cells)
+                .collect::<Vec<_>>(),
+            vec![vec![(0, 0), (0, 2)], vec![(0, 0), (0, 2)]]
+        );
+        assert_eq!(deltas[1].appeared, deltas[0].disappeared);
+    }
+}
@@ -0,0 +1,133 @@
+//! Generation of new puzzles with a unique solution
+use alloc::{vec, vec::Vec};
+
+use crate::{rng::Rng, Sudoku, EMPTY, SUDOKU_SIZE};
+
+/// Digits dropped at random on an empty grid before solving it: enough to make
+/// the solution random, few enough to almost never be contradictory
+const RANDOM_GIVENS: usize = 11;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct GeneratorConfig {
+    /// The same seed always generates the same puzzle
+    pub seed: u64,
+    /// Stop removing clues once this many are left, `0` removes as many as
+    /// possible while keeping the solution unique
+    pub target_clues: usize,
+    /// Keep clues symmetric under a 180° rotation, as published puzzles usually are
+    pub symmetric: bool,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            target_clues: 0,
+            symmetric: true,
+        }
+    }
+}
+
+/// Random complete and valid grid
+pub fn generate_solution(seed: u64) -> Sudoku {
+    let mut rng = Rng::new(seed);
+    loop {
+        let mut grid = Sudoku::empty();
+        for _ in 0..RANDOM_GIVENS {
+            let (row, col) = (rng.below(SUDOKU_SIZE), rng.below(SUDOKU_SIZE));
+            let value = rng.below(SUDOKU_SIZE) as u8 + 1;
+            if grid[(row, col)] == EMPTY && grid.find_conflict(row, col, value).is_none() {
+                grid[(row, col)] = value;
+            }
+        }
+        if let Some(solution) = grid.solve() {
+            return solution;
+        }
+    }
+}
+
+/// Random puzzle with exactly one solution.
+///
+/// Starting from a random solution, clues are removed in random order
+/// (symmetric pairs at once if asked) as long as the solution stays unique
+pub fn generate(config: &GeneratorConfig) -> Sudoku {
+    let mut puzzle = generate_solution(config.seed);
+    let mut rng = Rng::new(config.seed.wrapping_add(1));
+
+    let mut positions = (0..SUDOKU_SIZE * SUDOKU_SIZE)
+        .map(|index| (index / SUDOKU_SIZE, index % SUDOKU_SIZE))
+        .filter(|(row, col)| !config.symmetric || (*row, *col) <= (8 - row, 8 - col))
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut positions);
+
+    let mut clues = SUDOKU_SIZE * SUDOKU_SIZE;
+    for (row, col) in positions {
+        let mut removed = vec![(row, col)];
+        if config.symmetric && (row, col) != (8 - row, 8 - col) {
+            removed.push((8 - row, 8 - col));
+        }
+        if clues < config.target_clues + removed.len() {
+            continue;
+        }
+
+        let values = removed
+            .iter()
+            .map(|position| core::mem::replace(&mut puzzle[*position], EMPTY))
+            .collect::<Vec<_>>();
+        if puzzle.has_unique_solution() {
+            clues -= removed.len();
+        } else {
+            removed
+                .iter()
+                .zip(values)
+                .for_each(|(position, value)| puzzle[*position] = value);
+        }
+    }
+
+    puzzle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValidationProfile;
+
+    #[test]
+    fn test_generate_solution() {
+        let solution = generate_solution(7);
+
+        assert_eq!(solution, generate_solution(7));
+        assert_ne!(solution, generate_solution(8));
+        assert!(solution
+            .validate_profile(ValidationProfile::Complete)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_generate() {
+        let config = GeneratorConfig {
+            seed: 3,
+            ..Default::default()
+        };
+        let puzzle = generate(&config);
+
+        assert_eq!(puzzle, generate(&config));
+        assert!(puzzle.has_unique_solution());
+        assert!(puzzle
+            .cells()
+            .all(|((row, col), value)| (value == EMPTY) == (puzzle[(8 - row, 8 - col)] == EMPTY)));
+
+        let puzzle = generate(&GeneratorConfig {
+            seed: 3,
+            target_clues: 40,
+            symmetric: false,
+        });
+        assert_eq!(
+            puzzle.cells().filter(|(_, value)| *value != EMPTY).count(),
+            40
+        );
+        assert!(puzzle
+            .validate_profile(ValidationProfile::ProperPuzzle)
+            .is_ok());
+    }
+}
@@ -0,0 +1,120 @@
+//! Checking a player's attempt against the puzzle it started from
+use alloc::vec::Vec;
+
+use crate::{CellDiff, Sudoku};
+
+/// Outcome of [`Sudoku::grade`]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GradeReport {
+    /// Givens the attempt overwrote or erased
+    pub altered_givens: Vec<(usize, usize)>,
+    /// Filled cells disagreeing with the unique solution, altered givens included.
+    /// `None` when the puzzle has no unique solution to compare with
+    pub conflicts: Option<Vec<(usize, usize)>>,
+    /// Cells empty in the puzzle that the attempt filled
+    pub filled: usize,
+    /// Cells empty in the puzzle
+    pub to_fill: usize,
+}
+
+impl GradeReport {
+    /// Share of the cells to fill that hold a value, rounded down.
+    /// A puzzle without empty cells is complete
+    pub fn completion(&self) -> u8 {
+        match self.to_fill {
+            0 => 100,
+            to_fill => (self.filled * 100 / to_fill) as u8,
+        }
+    }
+
+    /// Every given kept, every cell filled and no conflict with the solution
+    pub fn is_solved(&self) -> bool {
+        self.altered_givens.is_empty()
+            && self.filled == self.to_fill
+            && self.conflicts.as_ref().is_some_and(Vec::is_empty)
+    }
+}
+
+impl Sudoku {
+    /// Grade `attempt`, a partially or fully filled copy of this puzzle
+    pub fn grade(&self, attempt: &Sudoku) -> GradeReport {
+        let from_puzzle = self.diff(attempt);
+        let altered_givens = from_puzzle
+            .positions(|cell| matches!(cell, CellDiff::Wrong { .. } | CellDiff::Missing { .. }))
+            .collect();
+        let to_fill = from_puzzle
+            .positions(|cell| matches!(cell, CellDiff::Empty | CellDiff::Extra { .. }))
+            .count();
+        let filled = from_puzzle
+            .positions(|cell| matches!(cell, CellDiff::Extra { .. }))
+            .count();
+
+        let mut solutions = self.solutions();
+        let conflicts = match (solutions.next(), solutions.next()) {
+            (Some(solution), None) => Some(
+                solution
+                    .diff(attempt)
+                    .positions(|cell| matches!(cell, CellDiff::Wrong { .. }))
+                    .collect(),
+            ),
+            _ => None,
+        };
+
+        GradeReport {
+            altered_givens,
+            conflicts,
+            filled,
+            to_fill,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EMPTY;
+    use alloc::{format, vec};
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_grade() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+
+        let untouched = puzzle.grade(&puzzle);
+        assert_eq!(untouched.altered_givens, vec![]);
+        assert_eq!(untouched.conflicts, Some(vec![]));
+        assert_eq!(untouched.to_fill, 51);
+        assert_eq!(untouched.completion(), 0);
+
+        let solved = puzzle.grade(&solution);
+        assert!(solved.is_solved());
+        assert_eq!(solved.completion(), 100);
+
+        let mut attempt = puzzle.clone();
+        attempt[(0, 0)] = 1;
+        attempt[(0, 1)] = EMPTY;
+        attempt[(0, 2)] = 4;
+        attempt[(0, 3)] = 7;
+        let report = puzzle.grade(&attempt);
+        assert_eq!(report.altered_givens, vec![(0, 0), (0, 1)]);
+        assert_eq!(report.conflicts, Some(vec![(0, 0), (0, 3)]));
+        assert_eq!(report.filled, 2);
+        assert_eq!(report.completion(), 3);
+        assert!(!report.is_solved());
+    }
+
+    #[test]
+    fn test_grade_ambiguous() {
+        let puzzle = Sudoku::from_line(&format!("..{}", &PUZZLE[2..])).unwrap();
+        let report = puzzle.grade(&Sudoku::from_line(SOLUTION).unwrap());
+
+        assert_eq!(report.conflicts, None);
+        assert_eq!(report.completion(), 100);
+        assert!(!report.is_solved());
+    }
+}
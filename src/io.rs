@@ -0,0 +1,287 @@
+//! Lazy reading and writing of puzzle collections too big to load at once
+//!
+//! A collection holds puzzles in the one-line format (see [`Sudoku::from_line`]),
+//! in the 9-line block format (see [`Sudoku::from_str`](core::str::FromStr)),
+//! or a mix of both. Blank lines separate blocks, `#` lines are comments.
+//! A line that isn't 81 cells long starts a block, which goes on for nine
+//! rows or up to the next blank line. A line that isn't valid UTF-8 is a
+//! malformed entry of its own, or spoils the block it's a row of
+use std::io::{self, BufRead, Write};
+
+use crate::{ParseError, Sudoku, SUDOKU_SIZE};
+
+/// One puzzle of a collection and where it starts
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Entry {
+    /// Line of the first row, counted from 1
+    pub line: usize,
+    /// Byte offset of the first row, to seek back to it
+    pub offset: u64,
+    pub puzzle: Result<Sudoku, ParseError>,
+}
+
+/// Iterator over the [`Entry`]s of a collection, reading one line at a time.
+///
+/// A malformed puzzle is an `Ok` entry with an `Err` puzzle and reading goes on,
+/// only I/O errors are yielded as `Err`
+pub struct SudokuReader<R> {
+    reader: R,
+    /// Current line, with its line break
+    buffer: Vec<u8>,
+    /// Number of lines read so far
+    line: usize,
+    /// Bytes read so far
+    offset: u64,
+}
+
+impl<R: BufRead> SudokuReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buffer: Vec::new(),
+            line: 0,
+            offset: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read the next line into the buffer, returns its number and offset
+    /// or `None` at the end of the input. Bytes are only decoded afterwards,
+    /// so the position stays right past a line that isn't valid UTF-8
+    fn next_line(&mut self) -> io::Result<Option<(usize, u64)>> {
+        self.buffer.clear();
+        let offset = self.offset;
+        let read = self.reader.read_until(b'\n', &mut self.buffer)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.offset += read as u64;
+        self.line += 1;
+        Ok(Some((self.line, offset)))
+    }
+
+    /// Current line without the surrounding whitespace
+    fn text(&self) -> Result<&str, ParseError> {
+        core::str::from_utf8(&self.buffer)
+            .map(str::trim)
+            .map_err(|_| ParseError::InvalidUtf8)
+    }
+
+    fn read_entry(&mut self) -> io::Result<Option<Entry>> {
+        let (line, offset) = loop {
+            let Some(position) = self.next_line()? else {
+                return Ok(None);
+            };
+            match self.text() {
+                Ok(text) if text.is_empty() || text.starts_with('#') => {}
+                _ => break position,
+            }
+        };
+
+        let puzzle = match self.text() {
+            Ok(first) if first.chars().count() == SUDOKU_SIZE * SUDOKU_SIZE => {
+                Sudoku::from_line(first)
+            }
+            Ok(first) => {
+                let first = String::from(first);
+                self.read_block(first)?
+            }
+            Err(err) => Err(err),
+        };
+        Ok(Some(Entry {
+            line,
+            offset,
+            puzzle,
+        }))
+    }
+
+    /// Read the rows following `first` and parse the whole block
+    fn read_block(&mut self, first: String) -> io::Result<Result<Sudoku, ParseError>> {
+        let mut block = Ok(first);
+        let mut rows = 1;
+        while rows < SUDOKU_SIZE && self.next_line()?.is_some() {
+            match self.text() {
+                Ok("") => break,
+                Ok(text) if text.starts_with('#') => continue,
+                Ok(text) => {
+                    if let Ok(block) = &mut block {
+                        block.push('\n');
+                        block.push_str(text);
+                    }
+                }
+                Err(err) => block = Err(err),
+            }
+            rows += 1;
+        }
+        Ok(block.and_then(|block| block.parse()))
+    }
+}
+
+impl<R: BufRead> Iterator for SudokuReader<R> {
+    type Item = io::Result<Entry>;
+
+    fn next(&mut self) -> Option<io::Result<Entry>> {
+        self.read_entry().transpose()
+    }
+}
+
+/// Layout of the puzzles written by [`SudokuWriter`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Format {
+    /// One line per puzzle, see [`Sudoku::to_line`]
+    #[default]
+    Line,
+    /// Nine lines per puzzle and a blank line between puzzles
+    Block,
+}
+
+/// Writes puzzles one by one in a format [`SudokuReader`] reads back
+pub struct SudokuWriter<W: Write> {
+    writer: W,
+    format: Format,
+    written: usize,
+}
+
+impl<W: Write> SudokuWriter<W> {
+    pub fn new(writer: W, format: Format) -> Self {
+        Self {
+            writer,
+            format,
+            written: 0,
+        }
+    }
+
+    pub fn write(&mut self, sudoku: &Sudoku) -> io::Result<()> {
+        let line = sudoku.to_line();
+        match self.format {
+            Format::Line => writeln!(self.writer, "{line}")?,
+            Format::Block => {
+                if self.written > 0 {
+                    writeln!(self.writer)?;
+                }
+                for row in line.as_bytes().chunks(SUDOKU_SIZE) {
+                    self.writer.write_all(row)?;
+                    writeln!(self.writer)?;
+                }
+            }
+        }
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Number of puzzles written so far
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Flush and give the underlying writer back
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_read_mixed_collection() {
+        let input = format!(
+            "# collection\n{PUZZLE}\r\n\n{}\n\n53x\n\n{SOLUTION}\n{}\n",
+            SOLUTION
+                .as_bytes()
+                .chunks(SUDOKU_SIZE)
+                .map(|row| std::str::from_utf8(row).unwrap())
+                .collect::<Vec<_>>()
+                .join("\n# a comment inside of a block\n"),
+            &PUZZLE[1..],
+        );
+        let entries = SudokuReader::new(input.as_bytes())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.line, entry.puzzle.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (2, Ok(puzzle)),
+                (4, Ok(solution.clone())),
+                (22, Err(ParseError::WrongSymbol('x'))),
+                (24, Ok(solution)),
+                (25, Err(ParseError::WrongRowSize { index: 0, len: 80 })),
+            ]
+        );
+        assert_eq!(entries[1].offset, 97);
+        assert_eq!(
+            &input[entries[3].offset as usize..][..SUDOKU_SIZE * SUDOKU_SIZE],
+            SOLUTION
+        );
+    }
+
+    #[test]
+    fn test_read_invalid_utf8() {
+        let mut input = b"\xff\xfe bad\n".to_vec();
+        input.extend_from_slice(format!("{PUZZLE}\n").as_bytes());
+        // The broken row still counts as one of the nine
+        let mut rows = SOLUTION.as_bytes().chunks(SUDOKU_SIZE).collect::<Vec<_>>();
+        rows[4] = b"\xc3(";
+        input.extend_from_slice(&rows.join(&b'\n'));
+        input.extend_from_slice(format!("\n{SOLUTION}\n").as_bytes());
+
+        let entries = SudokuReader::new(input.as_slice())
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            entries
+                .iter()
+                .map(|entry| (entry.line, entry.offset, entry.puzzle.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 0, Err(ParseError::InvalidUtf8)),
+                (2, 7, Ok(Sudoku::from_line(PUZZLE).unwrap())),
+                (3, 89, Err(ParseError::InvalidUtf8)),
+                (12, 172, Ok(Sudoku::from_line(SOLUTION).unwrap())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_read_round_trip() {
+        let puzzles = [
+            Sudoku::from_line(PUZZLE).unwrap(),
+            Sudoku::from_line(SOLUTION).unwrap(),
+        ];
+
+        for format in [Format::Line, Format::Block] {
+            let mut writer = SudokuWriter::new(Vec::new(), format);
+            puzzles
+                .iter()
+                .try_for_each(|sudoku| writer.write(sudoku))
+                .unwrap();
+            assert_eq!(writer.written(), 2);
+            let output = writer.into_inner().unwrap();
+
+            let read = SudokuReader::new(output.as_slice())
+                .map(|entry| entry.unwrap().puzzle.unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(read, puzzles);
+        }
+    }
+}
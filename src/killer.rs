@@ -0,0 +1,324 @@
+//! Killer Sudoku: a standard grid plus cages whose values must add up to
+//! a given sum without repeating a digit
+//!
+//! The text format is the usual grid, a blank line, then one cage per line:
+//!
+//! ```text
+//! <sum>: r<row>c<col> r<row>c<col> ...
+//! ```
+//!
+//! with rows and columns numbered from 1, as most puzzle books do
+use alloc::{
+    borrow::{Cow, ToOwned},
+    string::String,
+    vec::Vec,
+};
+use core::{fmt, str::FromStr};
+
+use crate::{
+    cell_char, unit_duplicates, ErrOr, Indexes, ParseError, RuleSet, Sudoku, ValidationError,
+    ValidationErrorType, EMPTY, SUDOKU_SIZE,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cage {
+    pub cells: Indexes,
+    pub sum: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KillerSudoku {
+    pub sudoku: Sudoku,
+    pub cages: Vec<Cage>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum KillerParseError {
+    Grid(ParseError),
+    /// Cage line without the `:` separator. Lines of every error are counted
+    /// from 1, from the start of the input
+    MissingSeparator {
+        line: usize,
+    },
+    WrongSum {
+        line: usize,
+    },
+    WrongCell {
+        line: usize,
+        cell: String,
+    },
+    /// A cage can't contain more cells than there are digits
+    TooManyCells {
+        line: usize,
+    },
+    /// A cage needs at least one cell
+    EmptyCage {
+        line: usize,
+    },
+    /// The cell belongs to more than one cage
+    OverlappingCell {
+        row: usize,
+        col: usize,
+    },
+}
+
+impl From<ParseError> for KillerParseError {
+    fn from(err: ParseError) -> Self {
+        Self::Grid(err)
+    }
+}
+
+fn parse_cell(cell: &str) -> Option<(usize, usize)> {
+    let (row, col) = cell.strip_prefix('r')?.split_once('c')?;
+    let (row, col) = (row.parse::<usize>().ok()?, col.parse::<usize>().ok()?);
+    ((1..=SUDOKU_SIZE).contains(&row) && (1..=SUDOKU_SIZE).contains(&col))
+        .then(|| (row - 1, col - 1))
+}
+
+impl FromStr for Cage {
+    type Err = KillerParseError;
+
+    /// Parse a single cage line, reported errors refer to line `1`
+    fn from_str(s: &str) -> Result<Self, KillerParseError> {
+        parse_cage(1, s)
+    }
+}
+
+fn parse_cage(line: usize, s: &str) -> Result<Cage, KillerParseError> {
+    use KillerParseError::*;
+
+    let (sum, cells) = s.split_once(':').ok_or(MissingSeparator { line })?;
+    let sum = sum.trim().parse().map_err(|_| WrongSum { line })?;
+
+    let mut indexes = Indexes::default();
+    for cell in cells.split_whitespace() {
+        let index = parse_cell(cell).ok_or_else(|| WrongCell {
+            line,
+            cell: cell.to_owned(),
+        })?;
+        if indexes.len() == SUDOKU_SIZE {
+            return Err(TooManyCells { line });
+        }
+        indexes.push(index);
+    }
+    if indexes.is_empty() {
+        return Err(EmptyCage { line });
+    }
+
+    Ok(Cage {
+        cells: indexes,
+        sum,
+    })
+}
+
+impl FromStr for KillerSudoku {
+    type Err = KillerParseError;
+
+    fn from_str(s: &str) -> Result<Self, KillerParseError> {
+        // `lines` also strips `\r`, so files with CRLF line endings parse the same
+        let mut lines = s.lines().enumerate();
+        let sudoku = lines
+            .by_ref()
+            .take_while(|(_, line)| !line.trim().is_empty())
+            .map(|(_, line)| line)
+            .collect::<Vec<_>>()
+            .join("\n")
+            .parse::<Sudoku>()?;
+
+        let cages = lines
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, cage)| parse_cage(index + 1, cage))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut owned = [[false; SUDOKU_SIZE]; SUDOKU_SIZE];
+        for &(row, col) in cages.iter().flat_map(|cage| cage.cells.iter()) {
+            if core::mem::replace(&mut owned[row][col], true) {
+                return Err(KillerParseError::OverlappingCell { row, col });
+            }
+        }
+
+        Ok(Self { sudoku, cages })
+    }
+}
+
+impl fmt::Display for KillerSudoku {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Values out of range are written as `?`, which the parser rejects
+        self.sudoku.grid.iter().try_for_each(|row| {
+            row.iter()
+                .try_for_each(|value| write!(f, "{}", cell_char(*value)))?;
+            writeln!(f)
+        })?;
+
+        self.cages.iter().try_for_each(|cage| {
+            write!(f, "\n{}:", cage.sum)?;
+            cage.cells
+                .iter()
+                .try_for_each(|(row, col)| write!(f, " r{}c{}", row + 1, col + 1))
+        })
+    }
+}
+
+impl KillerSudoku {
+    /// No duplicates in rows, columns and boxes plus, for every cage, no repeated
+    /// digits and a sum that is reached exactly once the cage is filled.
+    /// Empty cells are allowed, so a puzzle in progress can be checked too.
+    ///
+    /// A cage with cells outside of the grid is reported as a
+    /// [`Violation`](ValidationError::Violation) of the `"cage cells"` rule
+    /// listing them, and isn't checked any further
+    pub fn validate(self) -> Result<Self, Vec<ValidationError>> {
+        let mut errors = RuleSet::standard().check(&self.sudoku);
+
+        for (index, cage) in self.cages.iter().enumerate() {
+            let outside = cage
+                .cells
+                .iter()
+                .filter(|(row, col)| self.sudoku.get(*row, *col).is_none())
+                .copied()
+                .collect::<Indexes>();
+            if !outside.is_empty() {
+                errors.push(ValidationError::Violation {
+                    rule: Cow::Borrowed("cage cells"),
+                    indexes: outside,
+                });
+                continue;
+            }
+
+            let cells = cage
+                .cells
+                .iter()
+                .map(|position| (*position, self.sudoku[*position]));
+            errors.extend(unit_duplicates(
+                ValidationErrorType::Cage(index),
+                cells.clone(),
+            ));
+
+            let actual = cells
+                .clone()
+                .fold(0u8, |sum, (_, value)| sum.saturating_add(value));
+            let filled = cells.clone().all(|(_, value)| value != EMPTY);
+            if (filled && actual != cage.sum) || actual > cage.sum {
+                errors.push(ValidationError::CageSum {
+                    cage: index,
+                    expected: cage.sum,
+                    actual,
+                });
+            }
+        }
+
+        Some(errors)
+            .filter(|errors| !errors.is_empty())
+            .err_or(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KILLER: &str = "534678912\n\
+         672195348\n\
+         198342567\n\
+         859761423\n\
+         426853791\n\
+         713924856\n\
+         961537284\n\
+         287419635\n\
+         34528617.\n\
+         \n\
+         8: r1c1 r1c2\n\
+         17: r9c7 r9c8 r9c9\n";
+
+    #[test]
+    fn test_parse() {
+        let killer = KILLER.parse::<KillerSudoku>().unwrap();
+
+        assert_eq!(killer.cages.len(), 2);
+        assert_eq!(
+            killer.cages[1],
+            Cage {
+                cells: [(8, 6), (8, 7), (8, 8)].into_iter().collect(),
+                sum: 17
+            }
+        );
+        assert_eq!(
+            KILLER.replace('\n', "\r\n").parse::<KillerSudoku>(),
+            Ok(killer.clone())
+        );
+        assert_eq!(
+            killer.to_string().parse::<KillerSudoku>(),
+            Ok(killer.clone())
+        );
+
+        assert_eq!(
+            "x: r1c1".parse::<Cage>(),
+            Err(KillerParseError::WrongSum { line: 1 })
+        );
+        assert_eq!(
+            "3: r1c1 r0c2".parse::<Cage>(),
+            Err(KillerParseError::WrongCell {
+                line: 1,
+                cell: "r0c2".to_owned()
+            })
+        );
+        assert_eq!(
+            format!("{KILLER}3: r9c9\n").parse::<KillerSudoku>(),
+            Err(KillerParseError::OverlappingCell { row: 8, col: 8 })
+        );
+        // Lines count from the top of the input, grid and blank line included
+        assert_eq!(
+            format!("{KILLER}\n5:\n").parse::<KillerSudoku>(),
+            Err(KillerParseError::EmptyCage { line: 14 })
+        );
+        assert_eq!(
+            format!("{KILLER}5 r2c2\n").parse::<KillerSudoku>(),
+            Err(KillerParseError::MissingSeparator { line: 13 })
+        );
+
+        let mut broken = killer;
+        broken.sudoku[(0, 0)] = 12;
+        assert!(broken.to_string().starts_with("?34678912\n"));
+        assert!(broken.to_string().parse::<KillerSudoku>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut killer = KILLER.parse::<KillerSudoku>().unwrap();
+        // The last cell is empty, so the last cage isn't checked for its sum yet
+        killer = killer.validate().unwrap();
+
+        killer.sudoku[(8, 8)] = 9;
+        killer = killer.validate().unwrap();
+
+        killer.sudoku[(8, 8)] = 7;
+        let errors = killer.validate().unwrap_err();
+        assert!(errors.contains(&ValidationError::CageSum {
+            cage: 1,
+            expected: 17,
+            actual: 15
+        }));
+        assert!(errors.contains(&ValidationError::Dublication {
+            type_: ValidationErrorType::Cage(1),
+            value: 7,
+            indexes: [(8, 7), (8, 8)].into_iter().collect(),
+        }));
+    }
+
+    #[test]
+    fn test_validate_cage_outside_of_grid() {
+        let mut killer = KILLER.parse::<KillerSudoku>().unwrap();
+        killer.cages.push(Cage {
+            cells: [(9, 0), (8, 8), (0, 12)].into_iter().collect(),
+            sum: 3,
+        });
+
+        assert_eq!(
+            killer.validate(),
+            Err(vec![ValidationError::Violation {
+                rule: Cow::Borrowed("cage cells"),
+                indexes: [(9, 0), (0, 12)].into_iter().collect(),
+            }])
+        );
+    }
+}
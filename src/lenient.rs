@@ -0,0 +1,168 @@
+//! Parsing of hand-written or copy-pasted grids, reporting every problem at once
+use alloc::vec::Vec;
+
+use crate::{parse_cell, ParseError, Sudoku, EMPTY, SUDOKU_SIZE};
+
+/// Characters drawing the grid rather than holding cells
+fn is_decoration(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '|' | '-' | '+')
+}
+
+impl Sudoku {
+    /// Parse a grid the way [`FromStr`](core::str::FromStr) does, but ignoring
+    /// whitespace, `|`, `-` and `+` decorations and the lines left empty without them.
+    /// The one-line format of [`Sudoku::from_line`] is accepted too.
+    ///
+    /// All problems are reported, in input order, as
+    /// [`ParseError::WrongSymbolAt`], [`ParseError::WrongRowSizeAt`] and
+    /// finally [`ParseError::WrongColumnSize`]
+    ///
+    /// ```
+    /// use sudoku_tester::Sudoku;
+    ///
+    /// let grid = "
+    /// 5 3 . | . 7 . | . . .
+    /// 6 . . | 1 9 5 | . . .
+    /// . 9 8 | . . . | . 6 .
+    /// ------+-------+------
+    /// 8 . . | . 6 . | . . 3
+    /// 4 . . | 8 . 3 | . . 1
+    /// 7 . . | . 2 . | . . 6
+    /// ------+-------+------
+    /// . 6 . | . . . | 2 8 .
+    /// . . . | 4 1 9 | . . 5
+    /// . . . | . 8 . | . 7 9
+    /// ";
+    /// assert!(Sudoku::parse_lenient(grid).is_ok());
+    /// ```
+    pub fn parse_lenient(s: &str) -> Result<Sudoku, Vec<ParseError>> {
+        let mut errors = Vec::new();
+        let mut rows = Vec::new();
+
+        for (index, text) in s.lines().enumerate() {
+            let line = index + 1;
+            let cells = text
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| !is_decoration(*c))
+                .map(|(column, symbol)| {
+                    parse_cell(symbol).unwrap_or_else(|_| {
+                        errors.push(ParseError::WrongSymbolAt {
+                            symbol,
+                            line,
+                            column: column + 1,
+                        });
+                        EMPTY
+                    })
+                })
+                .collect::<Vec<_>>();
+            if !cells.is_empty() {
+                rows.push((line, cells));
+            }
+        }
+
+        if let [(line, cells)] = rows.as_slice() {
+            if cells.len() == SUDOKU_SIZE * SUDOKU_SIZE {
+                rows = cells
+                    .chunks(SUDOKU_SIZE)
+                    .map(|row| (*line, row.to_vec()))
+                    .collect();
+            }
+        }
+
+        errors.extend(
+            rows.iter()
+                .filter(|(_, cells)| cells.len() != SUDOKU_SIZE)
+                .map(|(line, cells)| ParseError::WrongRowSizeAt {
+                    line: *line,
+                    len: cells.len(),
+                }),
+        );
+        if rows.len() != SUDOKU_SIZE {
+            errors.push(ParseError::WrongColumnSize {
+                column_count: rows.len(),
+            });
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let mut grid = [[EMPTY; SUDOKU_SIZE]; SUDOKU_SIZE];
+        for (row, (_, cells)) in grid.iter_mut().zip(rows) {
+            row.copy_from_slice(&cells);
+        }
+        Ok(Sudoku { grid })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{format, vec};
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_parse_lenient() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let decorated = "
++-------+-------+-------+
+| 5 3 0 | 0 7 0 | 0 0 0 |
+| 6 0 0 | 1 9 5 | 0 0 0 |
+| 0 9 8 | 0 0 0 | 0 6 0 |
++-------+-------+-------+
+| 8 0 0 | 0 6 0 | 0 0 3 |
+| 4 0 0 | 8 0 3 | 0 0 1 |
+| 7 0 0 | 0 2 0 | 0 0 6 |
++-------+-------+-------+
+| 0 6 0 | 0 0 0 | 2 8 0 |
+| 0 0 0 | 4 1 9 | 0 0 5 |
+| 0 0 0 | 0 8 0 | 0 7 9 |
++-------+-------+-------+
+";
+
+        assert_eq!(Sudoku::parse_lenient(decorated), Ok(puzzle.clone()));
+        assert_eq!(Sudoku::parse_lenient(PUZZLE), Ok(puzzle.clone()));
+        assert_eq!(
+            Sudoku::parse_lenient(&format!("\n\t{PUZZLE}  \n\n")),
+            Ok(puzzle)
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient_errors() {
+        let grid = "\
+534|678|912
+672|19x|348
+
+198|342|56
+859|761|423
+426|853|791
+713|924|856
+961|5y7|284
+287|419|635";
+
+        assert_eq!(
+            Sudoku::parse_lenient(grid),
+            Err(vec![
+                ParseError::WrongSymbolAt {
+                    symbol: 'x',
+                    line: 2,
+                    column: 7
+                },
+                ParseError::WrongSymbolAt {
+                    symbol: 'y',
+                    line: 8,
+                    column: 6
+                },
+                ParseError::WrongRowSizeAt { line: 4, len: 8 },
+                ParseError::WrongColumnSize { column_count: 8 },
+            ])
+        );
+        assert_eq!(
+            Sudoku::parse_lenient(" | \n---"),
+            Err(vec![ParseError::WrongColumnSize { column_count: 0 }])
+        );
+    }
+}
@@ -1,12 +1,115 @@
 #![doc = include_str!("../README.md")]
-#![feature(iterator_try_collect)]
-use std::{fmt, str::FromStr};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+extern crate alloc;
 
-use some_to_err::ErrOr;
-use tinyvec::ArrayVec;
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{
+    fmt,
+    ops::{BitOr, Index, IndexMut},
+    str::FromStr,
+};
 
-const SUDOKU_SIZE: usize = 9;
-#[derive(PartialEq, Eq, Debug)]
+use tinyvec::TinyVec;
+
+#[cfg(feature = "rayon")]
+pub mod batch;
+mod bounded;
+mod builder;
+pub mod candidates;
+pub mod certificate;
+pub mod compress;
+pub mod constraint;
+#[cfg(feature = "unstable")]
+pub mod daily;
+mod diff;
+#[cfg(feature = "unstable")]
+mod dlx;
+pub mod encode;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+pub mod fuzz;
+#[cfg(feature = "std")]
+pub mod game;
+#[cfg(feature = "unstable")]
+pub mod generator;
+mod grade;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod killer;
+mod lenient;
+pub mod linked;
+mod minimal;
+mod profile;
+#[cfg(feature = "unstable")]
+mod quality;
+mod quick;
+#[cfg(all(feature = "unstable", feature = "std"))]
+pub mod rating;
+pub mod render;
+mod report;
+mod rng;
+pub mod samurai;
+#[cfg(feature = "serde")]
+mod schema;
+mod similarity;
+// `Sudoku::solve` and the other solving methods are stable, the solver
+// backends and search traces behind them are not
+#[cfg(feature = "unstable")]
+pub mod solver;
+#[cfg(not(feature = "unstable"))]
+mod solver;
+#[cfg(feature = "unstable")]
+pub mod techniques;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod transform;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use bounded::ErrorList;
+pub use builder::{SetError, SudokuBuilder};
+#[cfg(feature = "std")]
+pub use constraint::CheckProfile;
+pub use constraint::{Constraint, RuleSet};
+pub use diff::{CellDiff, GridDiff};
+pub use grade::GradeReport;
+pub use profile::ValidationProfile;
+pub use quick::QuickRejectReason;
+pub use report::{ReportDiff, Severity, ValidationReport};
+#[cfg(feature = "serde")]
+pub use schema::SCHEMA_VERSION;
+pub use solver::Solutions;
+
+pub const SUDOKU_SIZE: usize = 9;
+/// Value used in the grid for a cell that has not been filled yet
+pub const EMPTY: u8 = 0;
+
+/// Cell position and its value, as yielded by the grid iterators
+pub type Cell = ((usize, usize), u8);
+
+/// One of the 27 groups of nine cells that must hold every digit once.
+/// Indexes go from `0` to `8`: rows top to bottom, columns left to right,
+/// boxes in row-major order
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum House {
+    Row(usize),
+    Column(usize),
+    Box(usize),
+}
+
+impl From<House> for ValidationErrorType {
+    fn from(house: House) -> Self {
+        match house {
+            House::Row(i) => ValidationErrorType::Row(i),
+            House::Column(i) => ValidationErrorType::Column(i),
+            House::Box(i) => ValidationErrorType::Box(i),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
 pub struct Sudoku {
     // NOTE There are Sudoku's that are not standard
     // size, however, I think for simplicity they can
@@ -29,11 +132,35 @@ impl fmt::Display for Sudoku {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ParseError {
     WrongSymbol(char),
-    WrongRowSize { index: usize, len: usize },
-    WrongColumnSize { column_count: usize },
+    WrongRowSize {
+        index: usize,
+        len: usize,
+    },
+    WrongColumnSize {
+        column_count: usize,
+    },
+    WrongLineLength {
+        len: usize,
+    },
+    /// [`WrongSymbol`](ParseError::WrongSymbol) from [`Sudoku::parse_lenient`],
+    /// `line` and `column` count characters of the input from 1
+    WrongSymbolAt {
+        symbol: char,
+        line: usize,
+        column: usize,
+    },
+    /// [`WrongRowSize`](ParseError::WrongRowSize) from [`Sudoku::parse_lenient`],
+    /// `line` counts lines of the input from 1
+    WrongRowSizeAt {
+        line: usize,
+        len: usize,
+    },
+    /// The bytes of the puzzle aren't valid UTF-8, only reported when reading
+    /// raw input such as [`io::SudokuReader`]
+    InvalidUtf8,
 }
 
 impl FromStr for Sudoku {
@@ -47,18 +174,15 @@ impl FromStr for Sudoku {
             .enumerate()
             .map(|(row, line)| {
                 line.chars()
-                    .map(|c| match c.to_digit(10) {
-                        Some(val) => Ok(val as u8),
-                        None => Err(WrongSymbol(c)),
-                    })
-                    .try_collect::<Vec<u8>>()?
+                    .map(parse_cell)
+                    .collect::<Result<Vec<u8>, _>>()?
                     .try_into()
                     .map_err(|err: Vec<u8>| WrongRowSize {
                         index: row,
                         len: err.len(),
                     })
             })
-            .try_collect::<Vec<_>>()?
+            .collect::<Result<Vec<_>, _>>()?
             .try_into()
             .map_err(|err: Vec<[u8; SUDOKU_SIZE]>| WrongColumnSize {
                 column_count: err.len(),
@@ -68,107 +192,417 @@ impl FromStr for Sudoku {
     }
 }
 
-// Since this vec cannot be greater than 9,
-// we can use a data type that takes this into account!
-pub type Indexes = ArrayVec<[(usize, usize); 9]>;
+pub(crate) fn parse_cell(c: char) -> Result<u8, ParseError> {
+    match c {
+        '.' => Ok(EMPTY),
+        c => c
+            .to_digit(10)
+            .map(|val| val as u8)
+            .ok_or(ParseError::WrongSymbol(c)),
+    }
+}
+
+impl Sudoku {
+    /// Parse the one-line format used by most puzzle collections: 81 cells
+    /// in row-major order, where `0` or `.` marks an empty cell
+    pub fn from_line(line: &str) -> Result<Self, ParseError> {
+        let len = line.chars().count();
+        if len != SUDOKU_SIZE * SUDOKU_SIZE {
+            return Err(ParseError::WrongLineLength { len });
+        }
+
+        let mut grid = [[EMPTY; SUDOKU_SIZE]; SUDOKU_SIZE];
+        for (index, c) in line.chars().enumerate() {
+            grid[index / SUDOKU_SIZE][index % SUDOKU_SIZE] = parse_cell(c)?;
+        }
+
+        Ok(Sudoku { grid })
+    }
+
+    /// Inverse of [`Sudoku::from_line`], empty cells are written as `.`.
+    ///
+    /// Values above 9, which can only be set through [`IndexMut`], are written
+    /// as `?`, so the line is rejected when parsed back instead of changing meaning
+    pub fn to_line(&self) -> String {
+        self.grid.iter().flatten().copied().map(cell_char).collect()
+    }
+}
+
+/// Symbol of a value in the text formats: `.` for an empty cell and `?` for a
+/// value out of range, which no parser accepts
+pub fn cell_char(value: u8) -> char {
+    match value {
+        EMPTY => '.',
+        1..=9 => char::from(b'0' + value),
+        _ => '?',
+    }
+}
+
+/// The logic of `some_to_err::ErrOr`, which aggregates errors so nicely, kept
+/// in-crate since that crate needs `std`
+pub(crate) trait ErrOr<E> {
+    /// `Some(err)` becomes `Err(err)`, `None` becomes `Ok(ok)`
+    fn err_or<T>(self, ok: T) -> Result<T, E>;
+}
+
+impl<E> ErrOr<E> for Option<E> {
+    fn err_or<T>(self, ok: T) -> Result<T, E> {
+        match self {
+            Some(err) => Err(err),
+            None => Ok(ok),
+        }
+    }
+}
+
+// A house never holds more than 9 cells, so that many are stored inline.
+// Variant constraints (long thermometers, cages of cages, ...) may report more,
+// then the list moves to the heap instead of being truncated
+pub type Indexes = TinyVec<[(usize, usize); 9]>;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValidationErrorType {
     Column(usize),
     Row(usize),
     Box(usize),
+    /// `0` is the main diagonal, `1` is the anti-diagonal
+    Diagonal(usize),
+    /// Shaded region of Hyper-Sudoku, `0` to `3` in row-major order
+    ExtraRegion(usize),
+    /// Index of the cage in [`killer::KillerSudoku::cages`]
+    Cage(usize),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind")
+)]
 pub enum ValidationError {
+    #[cfg_attr(feature = "serde", serde(rename = "Duplication"))]
     Dublication {
+        #[cfg_attr(feature = "serde", serde(rename = "unit"))]
         type_: ValidationErrorType,
         value: u8,
+        #[cfg_attr(feature = "serde", serde(rename = "cells"))]
+        indexes: Indexes,
+    },
+    OutOfRange {
+        index: (usize, usize),
+        value: u8,
+    },
+    Incomplete {
+        empty_count: usize,
+    },
+    Unsolvable,
+    MultipleSolutions,
+    /// Values of a cage don't add up to its sum: either the cage is filled
+    /// and the sum differs, or the filled part already exceeds it
+    CageSum {
+        cage: usize,
+        expected: u8,
+        actual: u8,
+    },
+    /// Reported by a custom [`Constraint`], `rule` names it
+    Violation {
+        rule: Cow<'static, str>,
+        #[cfg_attr(feature = "serde", serde(rename = "cells"))]
         indexes: Indexes,
     },
 }
 
-impl Sudoku {
-    pub fn validate(self) -> Result<Self, Vec<ValidationError>> {
-        #[derive(Default)]
-        enum Number {
-            #[default]
-            Unknown,
-            Present {
-                row: usize,
-                col: usize,
-            },
-            Corrupted(Indexes),
+/// Which units must be free of duplicates. Rows, columns and boxes are always
+/// checked, variants add their own units on top, rules combine with `|`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Rules {
+    diagonals: bool,
+    extra_regions: bool,
+}
+
+impl Rules {
+    pub const STANDARD: Self = Self {
+        diagonals: false,
+        extra_regions: false,
+    };
+    /// Both main diagonals must contain every digit once
+    pub const X_SUDOKU: Self = Self {
+        diagonals: true,
+        extra_regions: false,
+    };
+    /// The four shaded 3x3 regions of Hyper-Sudoku (also known as Windoku),
+    /// one row and one column away from the border and from each other,
+    /// must contain every digit once
+    pub const HYPER: Self = Self {
+        diagonals: false,
+        extra_regions: true,
+    };
+}
+
+impl BitOr for Rules {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            diagonals: self.diagonals || rhs.diagonals,
+            extra_regions: self.extra_regions || rhs.extra_regions,
         }
+    }
+}
+
+/// Position of the `index`-th cell (in row-major order) of the Hyper-Sudoku region `region`
+fn extra_region_cell(region: usize, index: usize) -> (usize, usize) {
+    (
+        1 + (region / 2) * 4 + index / 3,
+        1 + (region % 2) * 4 + index % 3,
+    )
+}
 
-        impl Number {
-            fn indicate(&mut self, new_row: usize, new_col: usize) {
-                match self {
-                    Self::Unknown => {
-                        *self = Self::Present {
-                            row: new_row,
-                            col: new_col,
-                        };
-                    }
-                    Self::Present { row, col } => {
-                        *self = Self::Corrupted(
-                            [(*row, *col), (new_row, new_col)].into_iter().collect(),
-                        )
-                    }
-                    Self::Corrupted(ref mut indexes) => {
-                        indexes.push((new_row, new_col));
-                    }
+impl Index<(usize, usize)> for Sudoku {
+    type Output = u8;
+
+    fn index(&self, (row, col): (usize, usize)) -> &u8 {
+        &self.grid[row][col]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Sudoku {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut u8 {
+        &mut self.grid[row][col]
+    }
+}
+
+/// Box through the cell at `row`, `col`, numbered in row-major order
+pub(crate) fn box_index(row: usize, col: usize) -> usize {
+    (row / 3) * 3 + col / 3
+}
+
+/// Position of the `index`-th cell (in row-major order) of the box `box_index`
+fn box_cell(box_index: usize, index: usize) -> (usize, usize) {
+    (
+        (box_index / 3) * 3 + index / 3,
+        (box_index % 3) * 3 + index % 3,
+    )
+}
+
+impl Sudoku {
+    /// Value of a cell, [`EMPTY`] for an unfilled one and `None` if
+    /// the position is outside of the grid
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        self.grid.get(row)?.get(col).copied()
+    }
+
+    /// All cells in row-major order: `(0, 0)`, `(0, 1)`, ..., `(0, 8)`, `(1, 0)`, ..., `(8, 8)`
+    pub fn cells(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.grid.iter().enumerate().flat_map(|(row, values)| {
+            values
+                .iter()
+                .enumerate()
+                .map(move |(col, value)| ((row, col), *value))
+        })
+    }
+
+    /// Rows from top to bottom, cells of each row from left to right
+    pub fn rows(&self) -> impl Iterator<Item = [Cell; SUDOKU_SIZE]> + '_ {
+        (0..SUDOKU_SIZE).map(|row| self.cells_in(House::Row(row)))
+    }
+
+    /// Columns from left to right, cells of each column from top to bottom
+    pub fn columns(&self) -> impl Iterator<Item = [Cell; SUDOKU_SIZE]> + '_ {
+        (0..SUDOKU_SIZE).map(|col| self.cells_in(House::Column(col)))
+    }
+
+    /// Boxes in row-major order, cells of each box in row-major order too
+    pub fn boxes(&self) -> impl Iterator<Item = [Cell; SUDOKU_SIZE]> + '_ {
+        (0..SUDOKU_SIZE).map(|box_index| self.cells_in(House::Box(box_index)))
+    }
+
+    /// All 27 houses with their cells: the rows, then the columns, then the boxes,
+    /// each group in the order of [`Sudoku::rows`], [`Sudoku::columns`] and [`Sudoku::boxes`]
+    pub fn houses(&self) -> impl Iterator<Item = (House, [Cell; SUDOKU_SIZE])> + '_ {
+        let indexes = 0..SUDOKU_SIZE;
+        indexes
+            .clone()
+            .map(House::Row)
+            .chain(indexes.clone().map(House::Column))
+            .chain(indexes.map(House::Box))
+            .map(|house| (house, self.cells_in(house)))
+    }
+
+    /// Cells of one house: a row from left to right, a column from top to bottom
+    /// and a box in row-major order.
+    ///
+    /// # Panics
+    /// If the house index is not below [`SUDOKU_SIZE`]
+    pub fn cells_in(&self, house: House) -> [Cell; SUDOKU_SIZE] {
+        core::array::from_fn(|i| {
+            let position = match house {
+                House::Row(row) => (row, i),
+                House::Column(col) => (i, col),
+                House::Box(box_index) => box_cell(box_index, i),
+            };
+            (position, self[position])
+        })
+    }
+}
+
+#[derive(Default)]
+enum Number {
+    #[default]
+    Unknown,
+    Present {
+        row: usize,
+        col: usize,
+    },
+    Corrupted(Indexes),
+}
+
+impl Number {
+    fn indicate(&mut self, new_row: usize, new_col: usize) {
+        match self {
+            Self::Unknown => {
+                *self = Self::Present {
+                    row: new_row,
+                    col: new_col,
                 };
             }
-            fn into_err(self, value: u8, type_: ValidationErrorType) -> Option<ValidationError> {
-                match self {
-                    Self::Corrupted(indexes) => Some(ValidationError::Dublication {
-                        type_,
-                        value,
-                        indexes,
-                    }),
-                    _ => None,
-                }
+            Self::Present { row, col } => {
+                *self = Self::Corrupted([(*row, *col), (new_row, new_col)].into_iter().collect())
             }
-        }
-        let mut row_seen: [[Number; SUDOKU_SIZE]; SUDOKU_SIZE] = Default::default();
-        let mut column_seen: [[Number; SUDOKU_SIZE]; SUDOKU_SIZE] = Default::default();
-        let mut boxes_seen: [[Number; SUDOKU_SIZE]; SUDOKU_SIZE] = Default::default();
-
-        for (i, row) in self.grid.iter().enumerate() {
-            for (j, val) in row.iter().enumerate() {
-                let box_index = (i / 3) * 3 + (j / 3);
-                let val = *val as usize;
-
-                row_seen[i][val - 1].indicate(i, j);
-                column_seen[j][val - 1].indicate(i, j);
-                boxes_seen[box_index][val - 1].indicate(i, j);
+            Self::Corrupted(ref mut indexes) => {
+                indexes.push((new_row, new_col));
             }
+        };
+    }
+    fn into_err(self, value: u8, type_: ValidationErrorType) -> Option<ValidationError> {
+        match self {
+            Self::Corrupted(indexes) => Some(ValidationError::Dublication {
+                type_,
+                value,
+                indexes,
+            }),
+            _ => None,
         }
+    }
+}
 
-        macro_rules! get_validation_errors {
-            ($seen:expr, $err_type:ident) => {
-                $seen.into_iter().enumerate().flat_map(|(i, values)| {
-                    values
-                        .into_iter()
-                        .enumerate()
-                        .filter_map(move |(value, v)| {
-                            v.into_err(value as u8 + 1, ValidationErrorType::$err_type(i))
-                        })
-                })
-            };
+/// Duplicated values inside of one unit (row, column, box, ...), ordered by value.
+/// Empty and out-of-range cells are ignored here
+pub(crate) fn unit_duplicates(
+    type_: ValidationErrorType,
+    cells: impl IntoIterator<Item = Cell>,
+) -> impl Iterator<Item = ValidationError> {
+    let mut seen: [Number; SUDOKU_SIZE] = Default::default();
+    for ((row, col), value) in cells {
+        if let Some(number) = (value as usize)
+            .checked_sub(1)
+            .and_then(|i| seen.get_mut(i))
+        {
+            number.indicate(row, col);
         }
+    }
+
+    seen.into_iter()
+        .enumerate()
+        .filter_map(move |(value, number)| number.into_err(value as u8 + 1, type_))
+}
+
+impl Sudoku {
+    /// Units every standard sudoku must keep free of duplicates:
+    /// all rows, then all columns, then all boxes
+    fn units(&self) -> impl Iterator<Item = (ValidationErrorType, [Cell; SUDOKU_SIZE])> + '_ {
+        self.houses().map(|(house, cells)| (house.into(), cells))
+    }
+
+    /// The main diagonal (top-left to bottom-right) followed by the anti-diagonal
+    fn diagonals(&self) -> impl Iterator<Item = (ValidationErrorType, [Cell; SUDOKU_SIZE])> + '_ {
+        (0..2).map(|diagonal| {
+            let cells = core::array::from_fn(|i| {
+                let position = match diagonal {
+                    0 => (i, i),
+                    _ => (i, SUDOKU_SIZE - 1 - i),
+                };
+                (position, self[position])
+            });
+            (ValidationErrorType::Diagonal(diagonal), cells)
+        })
+    }
+
+    /// The four regions of Hyper-Sudoku in row-major order, cells of each
+    /// region in row-major order too
+    fn extra_regions(
+        &self,
+    ) -> impl Iterator<Item = (ValidationErrorType, [Cell; SUDOKU_SIZE])> + '_ {
+        (0..4).map(|region| {
+            let cells = core::array::from_fn(|index| {
+                let position = extra_region_cell(region, index);
+                (position, self[position])
+            });
+            (ValidationErrorType::ExtraRegion(region), cells)
+        })
+    }
+
+    /// Check a completed grid: every cell holds a digit from 1 to 9 and no row,
+    /// column or box holds the same digit twice.
+    ///
+    /// Errors are reported in this order: [`OutOfRange`](ValidationError::OutOfRange)
+    /// cells, duplicates, then a single [`Incomplete`](ValidationError::Incomplete)
+    /// if cells are left empty. A grid still being filled is checked with
+    /// [`ValidationProfile::Partial`] or [`Sudoku::validate_against`] instead
+    pub fn validate(self) -> Result<Self, Vec<ValidationError>> {
+        self.validate_with(Rules::STANDARD)
+    }
+
+    /// Same as [`Sudoku::validate`], with the additional units of a variant
+    ///
+    /// ```
+    /// use sudoku_tester::{Rules, Sudoku, ValidationError, ValidationErrorType};
+    ///
+    /// let sudoku = Sudoku::from_line(
+    ///     "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+    /// )
+    /// .unwrap();
+    /// let errors = sudoku.validate_with(Rules::X_SUDOKU).unwrap_err();
+    /// assert!(matches!(
+    ///     errors[0],
+    ///     ValidationError::Dublication { type_: ValidationErrorType::Diagonal(0), .. }
+    /// ));
+    /// ```
+    pub fn validate_with(self, rules: Rules) -> Result<Self, Vec<ValidationError>> {
+        let errors = self
+            .range_errors()
+            .chain(RuleSet::from(rules).check(&self))
+            .chain(self.incomplete_error())
+            .collect::<Vec<_>>();
 
-        // NOTE: A sensible compromise, bypass the elements twice (for a fixed number of variables
-        // this does not significantly affect performance), but get all possible errors.
-        //
-        // If this solution is too sub-optimal, I can replace `indicate(i, j)` with `validate(i, j)?`
-        // above and stop after first error
-        get_validation_errors!(row_seen, Row)
-            .chain(get_validation_errors!(column_seen, Column))
-            .chain(get_validation_errors!(boxes_seen, Box))
-            .map(Some)
-            .collect::<Option<_>>()
+        Some(errors)
+            .filter(|errors| !errors.is_empty())
+            .err_or(self)
+    }
+
+    /// Cells holding a value above 9, in row-major order
+    pub(crate) fn range_errors(&self) -> impl Iterator<Item = ValidationError> + '_ {
+        self.cells()
+            .filter(|(_, value)| *value as usize > SUDOKU_SIZE)
+            .map(|(index, value)| ValidationError::OutOfRange { index, value })
+    }
+
+    pub(crate) fn incomplete_error(&self) -> Option<ValidationError> {
+        let empty_count = self.cells().filter(|(_, value)| *value == EMPTY).count();
+        (empty_count > 0).then_some(ValidationError::Incomplete { empty_count })
+    }
+
+    /// Check every constraint of the rule set and collect all the errors found.
+    /// Unlike [`Sudoku::validate`], empty cells are allowed and values out of
+    /// range are left to the constraints
+    pub fn validate_against(self, rules: &RuleSet) -> Result<Self, Vec<ValidationError>> {
+        // NOTE: A sensible compromise, bypass all the constraints instead of stopping at the first
+        // duplicate (for a fixed number of variables this does not significantly affect
+        // performance), but get all possible errors.
+        let errors = rules.check(&self);
+
+        Some(errors)
+            .filter(|errors| !errors.is_empty())
             .err_or(self)
     }
 }
@@ -245,6 +679,213 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_valid_sudoku() {
+        let sudoku = Sudoku::from_line(
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+        )
+        .unwrap();
+
+        assert!(sudoku.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_returns_grid() {
+        // Errors used to be collected into an `Option`, which is `Some` even for
+        // an empty list: every grid was rejected, valid ones with no error at all
+        let sudoku = Sudoku::from_line(
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+        )
+        .unwrap();
+        assert_eq!(sudoku.clone().validate(), Ok(sudoku.clone()));
+
+        let mut invalid = sudoku;
+        invalid.grid[0].swap(0, 1);
+        assert!(!invalid.validate().unwrap_err().is_empty());
+    }
+
+    #[test]
+    fn test_from_line() {
+        let line =
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let sudoku = Sudoku::from_line(line).unwrap();
+
+        assert_eq!(sudoku.to_line(), line);
+        assert_eq!(
+            Sudoku::from_line(&line[1..]),
+            Err(ParseError::WrongLineLength { len: 80 })
+        );
+        assert!(sudoku.validate_against(&RuleSet::standard()).is_ok());
+    }
+
+    #[test]
+    fn test_to_line_out_of_range() {
+        let mut sudoku = Sudoku::empty();
+        sudoku[(0, 0)] = 12;
+        sudoku[(0, 1)] = 250;
+        sudoku[(0, 2)] = 9;
+
+        let line = sudoku.to_line();
+        assert!(line.starts_with("??9."));
+        assert_eq!(line.len(), 81);
+        assert_eq!(Sudoku::from_line(&line), Err(ParseError::WrongSymbol('?')));
+    }
+
+    #[test]
+    fn test_validate_strict() {
+        assert_eq!(
+            Sudoku::empty().validate(),
+            Err(vec![ValidationError::Incomplete { empty_count: 81 }])
+        );
+
+        let mut sudoku = Sudoku::from_line(
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+        )
+        .unwrap();
+        sudoku[(0, 0)] = 12;
+        sudoku[(8, 8)] = EMPTY;
+        assert_eq!(
+            sudoku.clone().validate(),
+            Err(vec![
+                ValidationError::OutOfRange {
+                    index: (0, 0),
+                    value: 12
+                },
+                ValidationError::Incomplete { empty_count: 1 }
+            ])
+        );
+        assert_eq!(
+            sudoku.clone().validate(),
+            sudoku.validate_profile(ValidationProfile::Complete)
+        );
+    }
+
+    #[test]
+    fn test_accessors() {
+        let mut sudoku = Sudoku::from_line(
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+        )
+        .unwrap();
+
+        assert_eq!(sudoku.get(0, 2), Some(4));
+        assert_eq!(sudoku.get(9, 0), None);
+        assert_eq!(sudoku[(8, 8)], 9);
+        sudoku[(8, 8)] = EMPTY;
+        assert_eq!(sudoku.get(8, 8), Some(EMPTY));
+
+        assert_eq!(sudoku.cells().count(), 81);
+        assert_eq!(sudoku.cells().nth(10), Some(((1, 1), 7)));
+        assert_eq!(sudoku.rows().nth(1).unwrap()[0], ((1, 0), 6));
+        assert_eq!(sudoku.columns().nth(1).unwrap()[2], ((2, 1), 9));
+        assert_eq!(
+            sudoku.boxes().last().unwrap().map(|(_, value)| value),
+            [2, 8, 4, 6, 3, 5, 1, 7, EMPTY]
+        );
+    }
+
+    #[test]
+    fn test_iteration_order() {
+        let sudoku = Sudoku::from_line(
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+        )
+        .unwrap();
+        let positions = |cells: [Cell; SUDOKU_SIZE]| cells.map(|(position, _)| position);
+
+        let cells = sudoku
+            .cells()
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        let row_major = (0..SUDOKU_SIZE)
+            .flat_map(|row| (0..SUDOKU_SIZE).map(move |col| (row, col)))
+            .collect::<Vec<_>>();
+        assert_eq!(cells, row_major);
+        assert_eq!(
+            sudoku.rows().flat_map(positions).collect::<Vec<_>>(),
+            row_major
+        );
+
+        let houses = sudoku.houses().collect::<Vec<_>>();
+        assert_eq!(houses.len(), 27);
+        assert_eq!(houses[0].0, House::Row(0));
+        assert_eq!(houses[9].0, House::Column(0));
+        assert_eq!(houses[26].0, House::Box(8));
+        assert!(houses
+            .iter()
+            .all(|&(house, cells)| cells == sudoku.cells_in(house)));
+        assert!(sudoku
+            .columns()
+            .eq(houses[9..18].iter().map(|(_, cells)| *cells)));
+
+        assert_eq!(
+            positions(sudoku.cells_in(House::Column(4)))[..3],
+            [(0, 4), (1, 4), (2, 4)]
+        );
+        assert_eq!(
+            positions(sudoku.cells_in(House::Box(5))),
+            [
+                (3, 6),
+                (3, 7),
+                (3, 8),
+                (4, 6),
+                (4, 7),
+                (4, 8),
+                (5, 6),
+                (5, 7),
+                (5, 8)
+            ]
+        );
+        assert_eq!(
+            sudoku.cells_in(House::Row(2)).map(|(_, value)| value),
+            [1, 9, 8, 3, 4, 2, 5, 6, 7]
+        );
+    }
+
+    #[test]
+    fn test_validate_x_sudoku() {
+        // Valid X-Sudoku: both diagonals contain every digit once
+        let mut sudoku = Sudoku::from_line(
+            "123456789456789123789123456214365897368972514597814632941638275832547961675291348",
+        )
+        .unwrap();
+        sudoku = sudoku
+            .validate_with(Rules::STANDARD | Rules::X_SUDOKU)
+            .unwrap();
+
+        // Swapping two columns of a stack keeps a standard sudoku valid, but not the diagonals
+        sudoku.grid.iter_mut().for_each(|row| row.swap(0, 1));
+        sudoku = sudoku.validate().unwrap();
+        assert_eq!(
+            sudoku.validate_with(Rules::X_SUDOKU).err().unwrap()[0],
+            ValidationError::Dublication {
+                type_: ValidationErrorType::Diagonal(0),
+                value: 2,
+                indexes: [(0, 0), (6, 6)].into_iter().collect(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_validate_hyper_sudoku() {
+        let sudoku = Sudoku::from_line(
+            "123456789456789123789123456534297861261834597897561234912378645348615972675942318",
+        )
+        .unwrap();
+        assert_eq!(sudoku.extra_regions().nth(1).unwrap().1[0], ((1, 5), 9));
+        let mut sudoku = sudoku.validate_with(Rules::HYPER).unwrap();
+
+        // Swapping the two first rows keeps the boxes, but breaks the top regions
+        sudoku.grid.swap(0, 1);
+        let errors = sudoku.validate_with(Rules::HYPER).unwrap_err();
+        assert!(!errors.is_empty());
+        assert!(errors.iter().all(|error| matches!(
+            error,
+            ValidationError::Dublication {
+                type_: ValidationErrorType::ExtraRegion(0 | 1),
+                ..
+            }
+        )));
+    }
+
     #[test]
     fn test_parse_wrong_sudoku_col() {
         let sudoku = "111111111\n\
@@ -0,0 +1,254 @@
+//! Constraints spanning several linked grids, as in Samurai and other Gattai
+//! puzzles: grids overlapping on shared boxes, or cells linked by equality.
+//!
+//! Single-grid [`Constraint`]s keep working on every grid through [`EachGrid`]
+//! and [`OnGrid`], links between grids are just more constraints
+use alloc::{borrow::Cow, boxed::Box, vec::Vec};
+
+use crate::{Constraint, Sudoku, ValidationError, EMPTY, SUDOKU_SIZE};
+
+/// Index of the grid, then row and column inside it
+pub type LinkedCell = (usize, (usize, usize));
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum LinkedError {
+    /// Error of a single-grid constraint
+    Grid { grid: usize, error: ValidationError },
+    /// Cells that must hold the same value don't
+    Mismatch {
+        cells: [LinkedCell; 2],
+        values: [u8; 2],
+    },
+    /// Reported by a custom [`LinkedConstraint`], `rule` names it
+    Violation {
+        rule: Cow<'static, str>,
+        cells: Vec<LinkedCell>,
+    },
+}
+
+pub trait LinkedConstraint {
+    /// Every violation found in the grids, empty cells must not be reported
+    fn check(&self, grids: &[Sudoku]) -> Vec<LinkedError>;
+}
+
+/// The same single-grid constraint applied to every grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EachGrid<C>(pub C);
+
+/// A single-grid constraint applied to the grid of the given index only
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnGrid<C>(pub usize, pub C);
+
+/// Pairs of cells that must hold the same value once both are filled.
+/// Cells outside of the grids are ignored
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Links {
+    pairs: Vec<[LinkedCell; 2]>,
+}
+
+impl<C: Constraint> LinkedConstraint for EachGrid<C> {
+    fn check(&self, grids: &[Sudoku]) -> Vec<LinkedError> {
+        grids
+            .iter()
+            .enumerate()
+            .flat_map(|(grid, sudoku)| {
+                self.0
+                    .check(sudoku)
+                    .into_iter()
+                    .map(move |error| LinkedError::Grid { grid, error })
+            })
+            .collect()
+    }
+}
+
+impl<C: Constraint> LinkedConstraint for OnGrid<C> {
+    fn check(&self, grids: &[Sudoku]) -> Vec<LinkedError> {
+        let OnGrid(grid, constraint) = self;
+        grids
+            .get(*grid)
+            .map(|sudoku| constraint.check(sudoku))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|error| LinkedError::Grid { grid: *grid, error })
+            .collect()
+    }
+}
+
+impl Links {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn link(mut self, first: LinkedCell, second: LinkedCell) -> Self {
+        self.pairs.push([first, second]);
+        self
+    }
+
+    /// Box `first.1` of grid `first.0` is the same box as `second.1` of grid `second.0`,
+    /// as the corner boxes of a Samurai
+    pub fn share_box(self, first: (usize, usize), second: (usize, usize)) -> Self {
+        let cell = |(grid, box_index): (usize, usize), index: usize| {
+            let (row, col) = (box_index / 3 * 3 + index / 3, box_index % 3 * 3 + index % 3);
+            (grid, (row, col))
+        };
+        (0..SUDOKU_SIZE).fold(self, |links, index| {
+            links.link(cell(first, index), cell(second, index))
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+fn value_at(grids: &[Sudoku], (grid, (row, col)): LinkedCell) -> Option<u8> {
+    grids
+        .get(grid)?
+        .get(row, col)
+        .filter(|value| *value != EMPTY)
+}
+
+impl LinkedConstraint for Links {
+    fn check(&self, grids: &[Sudoku]) -> Vec<LinkedError> {
+        self.pairs
+            .iter()
+            .filter_map(|&cells| {
+                let values = [value_at(grids, cells[0])?, value_at(grids, cells[1])?];
+                (values[0] != values[1]).then_some(LinkedError::Mismatch { cells, values })
+            })
+            .collect()
+    }
+}
+
+/// Ordered collection of linked constraints, the multi-grid counterpart of
+/// [`RuleSet`](crate::RuleSet)
+#[derive(Default)]
+pub struct LinkedRuleSet {
+    constraints: Vec<Box<dyn LinkedConstraint>>,
+}
+
+impl LinkedRuleSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, constraint: impl LinkedConstraint + 'static) -> Self {
+        self.push(constraint);
+        self
+    }
+
+    pub fn push(&mut self, constraint: impl LinkedConstraint + 'static) {
+        self.constraints.push(Box::new(constraint));
+    }
+
+    pub fn len(&self) -> usize {
+        self.constraints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    pub fn check(&self, grids: &[Sudoku]) -> Vec<LinkedError> {
+        self.constraints
+            .iter()
+            .flat_map(|constraint| constraint.check(grids))
+            .collect()
+    }
+
+    /// All the errors of `grids`, same as [`Sudoku::validate_against`] for one grid
+    pub fn validate(&self, grids: &[Sudoku]) -> Result<(), Vec<LinkedError>> {
+        let errors = self.check(grids);
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transform::Transformation, RuleSet, ValidationErrorType};
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    /// Two grids overlapping on one box: the bottom-right box of the first
+    /// is the top-left box of the second
+    fn twins() -> (Vec<Sudoku>, LinkedRuleSet) {
+        let first = Sudoku::from_line(SOLUTION).unwrap();
+        // Swapping the last two bands and stacks brings box 8 to box 0
+        let shift = Transformation::permute_rows([2, 0, 1], [[0, 1, 2]; 3])
+            .unwrap()
+            .then(&Transformation::permute_columns([2, 0, 1], [[0, 1, 2]; 3]).unwrap());
+        let second = shift.apply(&first);
+
+        let rules = LinkedRuleSet::new()
+            .with(EachGrid(RuleSet::standard()))
+            .with(Links::new().share_box((0, 8), (1, 0)));
+        (vec![first, second], rules)
+    }
+
+    #[test]
+    fn test_shared_box() {
+        let (mut grids, rules) = twins();
+        assert_eq!(rules.validate(&grids), Ok(()));
+
+        grids[1][(0, 0)] = EMPTY;
+        assert_eq!(rules.validate(&grids), Ok(()));
+
+        grids[1][(0, 0)] = 1;
+        assert_eq!(
+            rules.check(&grids),
+            vec![
+                LinkedError::Grid {
+                    grid: 1,
+                    error: ValidationError::Dublication {
+                        type_: ValidationErrorType::Row(0),
+                        value: 1,
+                        indexes: [(0, 0), (0, 5)].into_iter().collect(),
+                    },
+                },
+                LinkedError::Grid {
+                    grid: 1,
+                    error: ValidationError::Dublication {
+                        type_: ValidationErrorType::Column(0),
+                        value: 1,
+                        indexes: [(0, 0), (2, 0)].into_iter().collect(),
+                    },
+                },
+                LinkedError::Grid {
+                    grid: 1,
+                    error: ValidationError::Dublication {
+                        type_: ValidationErrorType::Box(0),
+                        value: 1,
+                        indexes: [(0, 0), (2, 0)].into_iter().collect(),
+                    },
+                },
+                LinkedError::Mismatch {
+                    cells: [(0, (6, 6)), (1, (0, 0))],
+                    values: [2, 1],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_on_grid() {
+        let (grids, _) = twins();
+        let rules = LinkedRuleSet::new()
+            .with(OnGrid(1, crate::constraint::Diagonals))
+            .with(OnGrid(5, crate::constraint::Diagonals));
+
+        let errors = rules.check(&grids);
+        assert!(!errors.is_empty());
+        assert!(errors
+            .iter()
+            .all(|error| matches!(error, LinkedError::Grid { grid: 1, .. })));
+    }
+}
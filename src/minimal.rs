@@ -0,0 +1,72 @@
+use crate::{Sudoku, EMPTY};
+
+impl Sudoku {
+    /// The solution is unique and every clue is needed for that:
+    /// removing any one of them lets a second solution in
+    pub fn is_minimal(&self) -> bool {
+        self.has_unique_solution()
+            && self
+                .cells()
+                .filter(|(_, value)| *value != EMPTY)
+                .all(|(position, _)| {
+                    let mut reduced = self.clone();
+                    reduced[position] = EMPTY;
+                    !reduced.has_unique_solution()
+                })
+    }
+
+    /// Same puzzle without its redundant clues, so the result [`is_minimal`](Sudoku::is_minimal).
+    ///
+    /// Clues are tried in row-major order and removed while the solution stays
+    /// unique. A clue that had to stay can't become redundant later, since removing
+    /// more clues never rules a solution out, so one pass is enough. Puzzles
+    /// without a unique solution are returned as they are
+    pub fn reduce(&self) -> Sudoku {
+        let mut reduced = self.clone();
+        if !reduced.has_unique_solution() {
+            return reduced;
+        }
+
+        for (position, value) in self.cells().filter(|(_, value)| *value != EMPTY) {
+            reduced[position] = EMPTY;
+            if !reduced.has_unique_solution() {
+                reduced[position] = value;
+            }
+        }
+        reduced
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_reduce() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        assert!(!puzzle.is_minimal());
+
+        let reduced = puzzle.reduce();
+        assert!(reduced.is_minimal());
+        assert_eq!(reduced.solve(), puzzle.solve());
+        assert!(reduced
+            .cells()
+            .all(|(position, value)| value == EMPTY || value == puzzle[position]));
+        assert_eq!(reduced.reduce(), reduced);
+
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+        assert!(solution.reduce().is_minimal());
+    }
+
+    #[test]
+    fn test_reduce_ambiguous() {
+        let empty = Sudoku::empty();
+        assert!(!empty.is_minimal());
+        assert_eq!(empty.reduce(), empty);
+    }
+}
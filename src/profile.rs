@@ -0,0 +1,194 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{ErrOr, RuleSet, Rules, Sudoku, ValidationError};
+
+/// Preset bundles of checks, so the meaning of "valid" is picked with one argument
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ValidationProfile {
+    /// A finished grid: every cell is filled and there are no duplicates
+    Complete,
+    /// A grid in progress: empty cells are allowed, filled ones must not conflict
+    Partial,
+    /// A puzzle fit for publishing: no conflicts among the givens and exactly one solution
+    ProperPuzzle,
+    /// A finished X-Sudoku grid, both main diagonals are checked as additional units
+    VariantX,
+}
+
+/// Duplicates are always checked, under `rules`, which the solvability and
+/// uniqueness checks use as well
+#[derive(Default)]
+struct Checks {
+    range: bool,
+    rules: Rules,
+    completeness: bool,
+    solvability: bool,
+    uniqueness: bool,
+}
+
+impl ValidationProfile {
+    fn checks(self) -> Checks {
+        let base = Checks {
+            range: true,
+            ..Default::default()
+        };
+
+        match self {
+            Self::Complete => Checks {
+                completeness: true,
+                ..base
+            },
+            Self::Partial => base,
+            Self::ProperPuzzle => Checks {
+                solvability: true,
+                uniqueness: true,
+                ..base
+            },
+            Self::VariantX => Checks {
+                rules: Rules::X_SUDOKU,
+                completeness: true,
+                ..base
+            },
+        }
+    }
+}
+
+impl Checks {
+    /// Solvability and uniqueness are only checked when all the other checks
+    /// have passed, since a grid with conflicts can't be solved anyway
+    fn run(&self, sudoku: &Sudoku) -> Vec<ValidationError> {
+        let mut errors = vec![];
+
+        if self.range {
+            errors.extend(sudoku.range_errors());
+        }
+        errors.extend(RuleSet::from(self.rules).check(sudoku));
+        if self.completeness {
+            errors.extend(sudoku.incomplete_error());
+        }
+        if errors.is_empty() && (self.solvability || self.uniqueness) {
+            match sudoku.count_solutions_with(self.rules, 2) {
+                0 => errors.push(ValidationError::Unsolvable),
+                2 if self.uniqueness => errors.push(ValidationError::MultipleSolutions),
+                _ => {}
+            }
+        }
+        errors
+    }
+}
+
+impl Sudoku {
+    /// Run every check of the `profile` and collect all the errors found
+    pub fn validate_profile(
+        self,
+        profile: ValidationProfile,
+    ) -> Result<Self, Vec<ValidationError>> {
+        let errors = profile.checks().run(&self);
+
+        Some(errors)
+            .filter(|errors| !errors.is_empty())
+            .err_or(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValidationErrorType, EMPTY, SUDOKU_SIZE};
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    fn sudoku(line: &str) -> Sudoku {
+        Sudoku::from_line(line).unwrap()
+    }
+
+    #[test]
+    fn test_complete_and_partial() {
+        assert!(sudoku(SOLUTION)
+            .validate_profile(ValidationProfile::Complete)
+            .is_ok());
+        assert_eq!(
+            sudoku(PUZZLE).validate_profile(ValidationProfile::Complete),
+            Err(vec![ValidationError::Incomplete { empty_count: 51 }])
+        );
+        assert!(sudoku(PUZZLE)
+            .validate_profile(ValidationProfile::Partial)
+            .is_ok());
+
+        let mut out_of_range = sudoku(PUZZLE);
+        out_of_range[(0, 2)] = 12;
+        assert_eq!(
+            out_of_range.validate_profile(ValidationProfile::Partial),
+            Err(vec![ValidationError::OutOfRange {
+                index: (0, 2),
+                value: 12
+            }])
+        );
+    }
+
+    #[test]
+    fn test_proper_puzzle() {
+        assert!(sudoku(PUZZLE)
+            .validate_profile(ValidationProfile::ProperPuzzle)
+            .is_ok());
+
+        let mut ambiguous = PUZZLE.to_owned();
+        ambiguous.replace_range(0..2, "..");
+        assert_eq!(
+            sudoku(&ambiguous).validate_profile(ValidationProfile::ProperPuzzle),
+            Err(vec![ValidationError::MultipleSolutions])
+        );
+
+        let mut unsolvable = PUZZLE.to_owned();
+        unsolvable.replace_range(2..3, "1");
+        assert_eq!(
+            sudoku(&unsolvable).validate_profile(ValidationProfile::ProperPuzzle),
+            Err(vec![ValidationError::Unsolvable])
+        );
+    }
+
+    #[test]
+    fn test_solver_checks_follow_rules() {
+        // The diagonals of a standard solution hold duplicates: once they are
+        // emptied, the givens are fine under X-Sudoku rules but can't be completed
+        let mut puzzle = sudoku(SOLUTION);
+        for i in 0..SUDOKU_SIZE {
+            puzzle[(i, i)] = EMPTY;
+            puzzle[(i, SUDOKU_SIZE - 1 - i)] = EMPTY;
+        }
+        let checks = Checks {
+            rules: Rules::X_SUDOKU,
+            solvability: true,
+            ..Default::default()
+        };
+
+        assert_eq!(checks.run(&puzzle), vec![ValidationError::Unsolvable]);
+        assert_eq!(
+            Checks {
+                rules: Rules::STANDARD,
+                ..checks
+            }
+            .run(&puzzle),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_variant_x() {
+        let errors = sudoku(SOLUTION)
+            .validate_profile(ValidationProfile::VariantX)
+            .unwrap_err();
+
+        assert!(!errors.is_empty());
+        assert!(errors.iter().all(|err| matches!(
+            err,
+            ValidationError::Dublication {
+                type_: ValidationErrorType::Diagonal(_),
+                ..
+            }
+        )));
+    }
+}
@@ -0,0 +1,138 @@
+//! Heuristic quality score, to rank generator output before a person reviews it
+use alloc::vec::Vec;
+
+use crate::{
+    techniques::{LogicalGrid, Technique},
+    Sudoku, EMPTY, SUDOKU_SIZE,
+};
+
+/// Clues symmetric under the most fitting symmetry of the grid
+const SYMMETRY_WEIGHT: u32 = 25;
+/// Few clues
+const CLUES_WEIGHT: u32 = 25;
+/// Many different techniques needed
+const VARIETY_WEIGHT: u32 = 30;
+/// No long stretch of singles
+const CHAINS_WEIGHT: u32 = 20;
+
+/// At most this many clues get the full clue count component
+const FEW_CLUES: u32 = 22;
+/// At least this many clues get none of it
+const MANY_CLUES: u32 = 36;
+
+const LAST: usize = SUDOKU_SIZE - 1;
+
+/// Image of a cell under a symmetry of the grid
+type Symmetry = fn((usize, usize)) -> (usize, usize);
+
+/// The symmetries published grids usually have: half-turn, both mirrors and both diagonals
+const SYMMETRIES: [Symmetry; 5] = [
+    |(row, col)| (LAST - row, LAST - col),
+    |(row, col)| (LAST - row, col),
+    |(row, col)| (row, LAST - col),
+    |(row, col)| (col, row),
+    |(row, col)| (LAST - col, LAST - row),
+];
+
+/// A component of the score, as a fraction
+struct Ratio(u32, u32);
+
+impl Ratio {
+    fn weighted(&self, weight: u32) -> u32 {
+        match self.1 {
+            0 => 0,
+            total => weight * self.0.min(total) / total,
+        }
+    }
+}
+
+impl Sudoku {
+    /// Aesthetic and solving quality of the puzzle from 0 to 100, the sum of:
+    ///
+    /// | Component | Weight | Full marks for |
+    /// |-----------|--------|----------------|
+    /// | Symmetry  | 25     | every clue mirrored by the best fitting of the half-turn, mirror and diagonal symmetries |
+    /// | Clues     | 25     | 22 clues or fewer, nothing from 36 on, linear in between |
+    /// | Variety   | 30     | all the logical [`Technique`]s needed to solve it |
+    /// | Chains    | 20     | no long run of singles: loses the share of the empty cells filled by the longest one |
+    ///
+    /// Puzzles without exactly one solution get `None`
+    pub fn quality_score(&self) -> Option<u8> {
+        if !self.has_unique_solution() {
+            return None;
+        }
+
+        let clues = self
+            .cells()
+            .filter(|(_, value)| *value != EMPTY)
+            .map(|(position, _)| position)
+            .collect::<Vec<_>>();
+        let clues_count = clues.len() as u32;
+
+        let symmetric = SYMMETRIES
+            .iter()
+            .map(|image| {
+                clues
+                    .iter()
+                    .filter(|position| self[image(**position)] != EMPTY)
+                    .count() as u32
+            })
+            .max()
+            .unwrap_or(0);
+        let symmetry = Ratio(symmetric, clues_count);
+
+        let clues = Ratio(
+            MANY_CLUES.saturating_sub(clues_count),
+            MANY_CLUES - FEW_CLUES,
+        );
+
+        let mut grid = LogicalGrid::new(self);
+        let mut used = [false; Technique::ALL.len()];
+        let (mut run, mut longest_run) = (0, 0);
+        while let Some(step) = grid.step() {
+            used[step.technique as usize] = true;
+            match step.technique {
+                Technique::NakedSingle | Technique::HiddenSingle => run += 1,
+                _ => run = 0,
+            }
+            longest_run = longest_run.max(run);
+        }
+        let variety = Ratio(
+            used.iter().filter(|used| **used).count() as u32,
+            Technique::ALL.len() as u32,
+        );
+        let empty_count = SUDOKU_SIZE as u32 * SUDOKU_SIZE as u32 - clues_count;
+        let chains = Ratio(empty_count.saturating_sub(longest_run), empty_count);
+
+        let score = symmetry.weighted(SYMMETRY_WEIGHT)
+            + clues.weighted(CLUES_WEIGHT)
+            + variety.weighted(VARIETY_WEIGHT)
+            + chains.weighted(CHAINS_WEIGHT);
+        Some(score as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const PAIRS_PUZZLE: &str =
+        "4.....938.32.941...953..24.37.6.9..4529..16736.47.3.9.957..83....39..4..24..3.7.9";
+
+    #[test]
+    fn test_quality_score() {
+        let easy = Sudoku::from_line(PUZZLE).unwrap();
+        let score = easy.quality_score().unwrap();
+        // Half-turn symmetric, 30 clues, solved by one run of naked singles
+        assert_eq!(score, 25 + 10 + 6);
+
+        let pairs = Sudoku::from_line(PAIRS_PUZZLE).unwrap();
+        assert!(pairs.quality_score().unwrap() <= 100);
+
+        let solved = easy.solve().unwrap();
+        assert!(solved.quality_score().unwrap() < score);
+        assert_eq!(Sudoku::empty().quality_score(), None);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    box_index, candidates::ALL_CANDIDATES, Sudoku, ValidationErrorType, EMPTY, SUDOKU_SIZE,
+};
+
+/// Why [`Sudoku::quick_reject`] discarded a grid
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum QuickRejectReason {
+    OutOfRange {
+        index: (usize, usize),
+        value: u8,
+    },
+    /// A digit appears more times than there are rows
+    DigitOverflow {
+        value: u8,
+    },
+    /// A house holds the same digit twice
+    Duplicate {
+        type_: ValidationErrorType,
+        value: u8,
+    },
+    /// An empty cell whose row, column and box already use every digit
+    NoCandidates {
+        index: (usize, usize),
+    },
+}
+
+impl Sudoku {
+    /// Cheap checks meant to discard garbage grids in bulk pipelines before
+    /// paying for full validation or solving: one pass over the givens, then a
+    /// second one over the empty cells to find one without candidates.
+    ///
+    /// `None` doesn't mean the grid is valid, only that it isn't obviously broken
+    pub fn quick_reject(&self) -> Option<QuickRejectReason> {
+        use QuickRejectReason::*;
+
+        let mut counts = [0usize; SUDOKU_SIZE + 1];
+        let mut rows = [0u16; SUDOKU_SIZE];
+        let mut columns = [0u16; SUDOKU_SIZE];
+        let mut boxes = [0u16; SUDOKU_SIZE];
+
+        for ((row, col), value) in self.cells() {
+            if value == EMPTY {
+                continue;
+            }
+            if value as usize > SUDOKU_SIZE {
+                return Some(OutOfRange {
+                    index: (row, col),
+                    value,
+                });
+            }
+
+            counts[value as usize] += 1;
+            if counts[value as usize] > SUDOKU_SIZE {
+                return Some(DigitOverflow { value });
+            }
+
+            let bit = 1 << (value - 1);
+            let box_index = box_index(row, col);
+            for (type_, mask) in [
+                (ValidationErrorType::Row(row), &mut rows[row]),
+                (ValidationErrorType::Column(col), &mut columns[col]),
+                (ValidationErrorType::Box(box_index), &mut boxes[box_index]),
+            ] {
+                if *mask & bit != 0 {
+                    return Some(Duplicate { type_, value });
+                }
+                *mask |= bit;
+            }
+        }
+
+        self.cells()
+            .filter(|(_, value)| *value == EMPTY)
+            .find(|((row, col), _)| {
+                rows[*row] | columns[*col] | boxes[box_index(*row, *col)] == ALL_CANDIDATES
+            })
+            .map(|(index, _)| NoCandidates { index })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_quick_reject() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        assert_eq!(puzzle.quick_reject(), None);
+        assert_eq!(puzzle.solve().unwrap().quick_reject(), None);
+
+        let mut broken = puzzle.clone();
+        broken[(0, 2)] = 11;
+        assert_eq!(
+            broken.quick_reject(),
+            Some(QuickRejectReason::OutOfRange {
+                index: (0, 2),
+                value: 11
+            })
+        );
+
+        let mut broken = puzzle.clone();
+        broken[(0, 8)] = 5;
+        assert_eq!(
+            broken.quick_reject(),
+            Some(QuickRejectReason::Duplicate {
+                type_: ValidationErrorType::Row(0),
+                value: 5
+            })
+        );
+
+        assert_eq!(
+            Sudoku::from_line(&"1".repeat(81)).unwrap().quick_reject(),
+            Some(QuickRejectReason::Duplicate {
+                type_: ValidationErrorType::Row(0),
+                value: 1
+            })
+        );
+
+        // Every 1 of the solution, then a tenth one in the last row
+        let solution = puzzle.solve().unwrap();
+        let mut crowded = Sudoku::empty();
+        solution
+            .cells()
+            .filter(|(_, value)| *value == 1)
+            .for_each(|(index, value)| crowded[index] = value);
+        crowded[(8, 8)] = 1;
+        assert_eq!(
+            crowded.quick_reject(),
+            Some(QuickRejectReason::DigitOverflow { value: 1 })
+        );
+
+        // Row 0 misses only 9, which column 8 already holds
+        let mut dead = Sudoku::empty();
+        (0..8).for_each(|col| dead[(0, col)] = col as u8 + 1);
+        dead[(5, 8)] = 9;
+        assert_eq!(
+            dead.quick_reject(),
+            Some(QuickRejectReason::NoCandidates { index: (0, 8) })
+        );
+    }
+}
@@ -0,0 +1,369 @@
+//! Difficulty rating of puzzles and bulk rating of large collections
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    io::{self, BufRead, Write},
+    ops::ControlFlow,
+};
+
+use crate::{
+    cell_char,
+    solver::Backtracker,
+    techniques::{LogicalGrid, Technique},
+    Sudoku, SUDOKU_SIZE,
+};
+
+/// Bumped every time the rating algorithm or the [`Fingerprint`] changes, so
+/// ratings cached by an older version of the crate are not reused
+pub const RATING_VERSION: u32 = 3;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Level {
+    /// Naked singles are enough
+    Easy,
+    /// Needs hidden singles
+    Medium,
+    /// Needs pairs or box/line intersections
+    Hard,
+    /// The known techniques get stuck, some guessing is required
+    Expert,
+}
+
+impl Level {
+    const ALL: [Level; 4] = [Level::Easy, Level::Medium, Level::Hard, Level::Expert];
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Difficulty {
+    pub level: Level,
+    /// The higher, the harder, only comparable between ratings of the same [`RATING_VERSION`]
+    pub score: u32,
+}
+
+/// Score of one deduction, the harder the technique the more it weighs
+fn weight(technique: Technique) -> u32 {
+    match technique {
+        Technique::NakedSingle => 1,
+        Technique::HiddenSingle => 2,
+        Technique::PointingPair => 8,
+        Technique::BoxLineReduction => 8,
+        Technique::NakedPair => 10,
+    }
+}
+
+/// Score of one branching decision of the solver, once the techniques are stuck
+const GUESS_WEIGHT: u32 = 100;
+
+/// Rate a puzzle by solving it the way a person would: always applying the simplest
+/// technique that makes progress, and guessing only when none does.
+///
+/// The level is set by the hardest technique needed (or by guessing), the score adds
+/// up a per-technique weight for every deduction and a much larger one per guess.
+/// Puzzles without exactly one solution have no meaningful difficulty and get `None`
+pub fn rate(sudoku: &Sudoku) -> Option<Difficulty> {
+    if !sudoku.has_unique_solution() {
+        return None;
+    }
+
+    let mut grid = LogicalGrid::new(sudoku);
+    let mut score = 0;
+    let mut level = Level::Easy;
+    while let Some(step) = grid.step() {
+        score += weight(step.technique);
+        level = level.max(match step.technique {
+            Technique::NakedSingle => Level::Easy,
+            Technique::HiddenSingle => Level::Medium,
+            _ => Level::Hard,
+        });
+    }
+
+    if !grid.is_solved() {
+        let mut state = Backtracker::new(grid.sudoku())?;
+        let _ = state.search(&mut |_| ControlFlow::<()>::Continue(()));
+        score += GUESS_WEIGHT * state.guesses().max(1) as u32;
+        level = Level::Expert;
+    }
+
+    Some(Difficulty { level, score })
+}
+
+impl Sudoku {
+    /// See [`rating::rate`](rate)
+    pub fn rate(&self) -> Option<Difficulty> {
+        rate(self)
+    }
+}
+
+/// Key identifying a puzzle up to any [`Transformation`](crate::transform::Transformation):
+/// the cells of its [`canonical form`](Sudoku::canonical_form).
+///
+/// Computing it takes a search over the transformations, in the order of a
+/// millisecond: more than rating an easy puzzle, far less than a hard one
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Fingerprint([u8; SUDOKU_SIZE * SUDOKU_SIZE]);
+
+impl Fingerprint {
+    pub fn of(sudoku: &Sudoku) -> Self {
+        Self::of_canonical(&sudoku.canonical_form())
+    }
+
+    fn of_canonical(canonical: &Sudoku) -> Self {
+        Self(*canonical.grid.as_flattened().as_array().unwrap())
+    }
+
+    /// Values above 9 can't be written by [`RatingCache::write_to`]
+    fn is_writable(&self) -> bool {
+        self.0.iter().all(|value| *value as usize <= SUDOKU_SIZE)
+    }
+}
+
+/// Ratings already computed, so re-rating a mostly unchanged collection
+/// only rates the new puzzles.
+///
+/// The search behind [`rate`] breaks ties in row-major order, so the score of
+/// an Expert puzzle varies between its transformed copies. The cache rates the
+/// canonical form instead, which gives every copy the same difficulty whatever
+/// order they come in, possibly not the one `rate` gives the copy itself.
+///
+/// It can be persisted between runs with [`RatingCache::write_to`] and [`RatingCache::read_from`]
+#[derive(Debug, Default, Clone)]
+pub struct RatingCache {
+    ratings: HashMap<Fingerprint, Option<Difficulty>>,
+    hits: usize,
+    misses: usize,
+}
+
+impl RatingCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ratings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ratings.is_empty()
+    }
+
+    /// Number of ratings served from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of ratings that had to be computed
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Difficulty of the [`canonical form`](Sudoku::canonical_form) of `sudoku`
+    pub fn rate(&mut self, sudoku: &Sudoku) -> Option<Difficulty> {
+        let canonical = sudoku.canonical_form();
+        let fingerprint = Fingerprint::of_canonical(&canonical);
+        match self.ratings.get(&fingerprint) {
+            Some(difficulty) => {
+                self.hits += 1;
+                *difficulty
+            }
+            None => {
+                self.misses += 1;
+                let difficulty = rate(&canonical);
+                self.ratings.insert(fingerprint, difficulty);
+                difficulty
+            }
+        }
+    }
+
+    /// Save as text: a version header, then one `<fingerprint> <level> <score>` line
+    /// per puzzle, with a single `-` for puzzles that can't be rated. Puzzles with
+    /// values above 9 are left out, they are rejected by [`rate`] at no cost
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "rating-cache v{RATING_VERSION}")?;
+        for (fingerprint, difficulty) in &self.ratings {
+            if !fingerprint.is_writable() {
+                continue;
+            }
+            let line = fingerprint.0.iter().copied().map(cell_char);
+            write!(writer, "{}", line.collect::<String>())?;
+            match difficulty {
+                Some(difficulty) => {
+                    writeln!(writer, " {:?} {}", difficulty.level, difficulty.score)?
+                }
+                None => writeln!(writer, " -")?,
+            }
+        }
+        writer.flush()
+    }
+
+    /// Load a cache saved by [`RatingCache::write_to`].
+    ///
+    /// A cache saved with another [`RATING_VERSION`] is stale and loads as empty
+    pub fn read_from<R: BufRead>(reader: R) -> io::Result<Self> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("malformed rating cache line: {line:?}"),
+            )
+        };
+
+        let mut lines = reader.lines();
+        let mut cache = Self::new();
+        match lines.next().transpose()? {
+            Some(header) if header == format!("rating-cache v{RATING_VERSION}") => {}
+            _ => return Ok(cache),
+        }
+
+        for line in lines {
+            let line = line?;
+            let (cells, rating) = line.split_once(' ').ok_or_else(|| invalid(&line))?;
+            let cells = Sudoku::from_line(cells).map_err(|_| invalid(&line))?;
+            let difficulty = match rating {
+                "-" => None,
+                rating => {
+                    let (level, score) = rating.split_once(' ').ok_or_else(|| invalid(&line))?;
+                    Some(Difficulty {
+                        level: Level::ALL
+                            .into_iter()
+                            .find(|known| format!("{known:?}") == level)
+                            .ok_or_else(|| invalid(&line))?,
+                        score: score.parse().map_err(|_| invalid(&line))?,
+                    })
+                }
+            };
+            cache.ratings.insert(
+                Fingerprint(cells.grid.as_flattened().try_into().unwrap()),
+                difficulty,
+            );
+        }
+
+        Ok(cache)
+    }
+}
+
+/// Rate every puzzle of a collection lazily, in input order, reusing and
+/// filling the `cache`
+pub fn rate_collection<'c, I>(
+    puzzles: I,
+    cache: &'c mut RatingCache,
+) -> impl Iterator<Item = Option<Difficulty>> + 'c
+where
+    I: IntoIterator,
+    I::Item: Borrow<Sudoku>,
+    I::IntoIter: 'c,
+{
+    puzzles
+        .into_iter()
+        .map(move |sudoku| cache.rate(sudoku.borrow()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transform::Transformation, EMPTY};
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const PAIRS_PUZZLE: &str =
+        "4.....938.32.941...953..24.37.6.9..4529..16736.47.3.9.957..83....39..4..24..3.7.9";
+    const HARD_PUZZLE: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+    #[test]
+    fn test_rate() {
+        let easy = Sudoku::from_line(PUZZLE).unwrap().rate().unwrap();
+        let hard = Sudoku::from_line(PAIRS_PUZZLE).unwrap().rate().unwrap();
+        let expert = Sudoku::from_line(HARD_PUZZLE).unwrap().rate().unwrap();
+        assert_eq!(
+            [easy.level, hard.level, expert.level],
+            [Level::Easy, Level::Hard, Level::Expert]
+        );
+        assert!(easy.score < hard.score && hard.score < expert.score);
+        assert_eq!(rate(&Sudoku::empty()), None);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let mut relabeled = sudoku.clone();
+        relabeled
+            .grid
+            .iter_mut()
+            .flatten()
+            .filter(|value| **value != EMPTY)
+            .for_each(|value| *value = *value % 9 + 1);
+
+        assert_eq!(Fingerprint::of(&sudoku), Fingerprint::of(&relabeled));
+        for seed in 0..8 {
+            assert_eq!(
+                Fingerprint::of(&sudoku),
+                Fingerprint::of(&Transformation::from_seed(seed).apply(&sudoku))
+            );
+        }
+        assert_ne!(
+            Fingerprint::of(&sudoku),
+            Fingerprint::of(&Sudoku::from_line(HARD_PUZZLE).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_rate_collection() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        let hard = Sudoku::from_line(HARD_PUZZLE).unwrap();
+        let puzzles = [
+            puzzle.clone(),
+            hard.clone(),
+            Transformation::from_seed(1).apply(&puzzle),
+        ];
+
+        let mut cache = RatingCache::new();
+        let ratings = rate_collection(&puzzles, &mut cache).collect::<Vec<_>>();
+        let (puzzle, hard) = (puzzle.canonical_form(), hard.canonical_form());
+        assert_eq!(ratings, vec![rate(&puzzle), rate(&hard), rate(&puzzle)]);
+        // The transformed puzzle shares the fingerprint of the original one
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        let mut saved = Vec::new();
+        cache.write_to(&mut saved).unwrap();
+        let mut cache = RatingCache::read_from(saved.as_slice()).unwrap();
+        assert_eq!(cache.len(), 2);
+
+        let ratings = rate_collection([&hard, &puzzle], &mut cache).collect::<Vec<_>>();
+        assert_eq!(ratings, vec![rate(&hard), rate(&puzzle)]);
+        assert_eq!((cache.hits(), cache.misses()), (2, 0));
+
+        let stale = RatingCache::read_from("rating-cache v0\n".as_bytes()).unwrap();
+        assert!(stale.is_empty());
+    }
+
+    #[test]
+    fn test_rate_collection_order() {
+        let hard = Sudoku::from_line(HARD_PUZZLE).unwrap();
+        let transformed = Transformation::from_seed(3).apply(&hard);
+        let expected = rate(&hard.canonical_form());
+        // The search itself depends on the orientation
+        assert_ne!(rate(&hard), rate(&transformed));
+
+        let forward =
+            rate_collection([&hard, &transformed], &mut RatingCache::new()).collect::<Vec<_>>();
+        let backward =
+            rate_collection([&transformed, &hard], &mut RatingCache::new()).collect::<Vec<_>>();
+        assert_eq!(forward, vec![expected; 2]);
+        assert_eq!(backward, forward);
+    }
+
+    #[test]
+    fn test_write_out_of_range() {
+        let mut broken = Sudoku::from_line(PUZZLE).unwrap();
+        broken[(0, 2)] = 12;
+        let mut cache = RatingCache::new();
+        cache.rate(&broken);
+        cache.rate(&Sudoku::from_line(PUZZLE).unwrap());
+
+        let mut saved = Vec::new();
+        cache.write_to(&mut saved).unwrap();
+        let text = String::from_utf8(saved).unwrap();
+        // Header and the rating of the valid puzzle only
+        assert_eq!(text.lines().count(), 2);
+        assert_eq!(RatingCache::read_from(text.as_bytes()).unwrap().len(), 1);
+    }
+}
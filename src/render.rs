@@ -0,0 +1,110 @@
+//! Overlay of two grids, e.g. a solution and a player's attempt, for grading
+//! reports: every cell shows whether it matches, is wrong, missing or extra
+use alloc::{
+    borrow::ToOwned,
+    format,
+    string::{String, ToString},
+};
+use core::fmt::Write;
+
+use crate::{CellDiff, GridDiff, SUDOKU_SIZE};
+
+/// Fixed-width text overlay, two characters per cell: a marker and a digit
+///
+/// ```text
+///  5   matches         x7  wrong (7 entered)
+///  _   missing         +7  extra (7 entered)
+///  .   empty in both
+/// ```
+pub fn overlay_text(diff: &GridDiff) -> String {
+    let separator = "+----------+----------+----------+\n";
+    let mut text = String::new();
+
+    for row in 0..SUDOKU_SIZE {
+        if row % 3 == 0 {
+            text.push_str(separator);
+        }
+        for col in 0..SUDOKU_SIZE {
+            if col % 3 == 0 {
+                text.push_str("| ");
+            }
+            let cell = match diff.get(row, col).unwrap_or(CellDiff::Empty) {
+                CellDiff::Empty => " .".to_owned(),
+                CellDiff::Match(value) => format!(" {value}"),
+                CellDiff::Wrong { actual, .. } => format!("x{actual}"),
+                CellDiff::Missing { .. } => " _".to_owned(),
+                CellDiff::Extra { actual } => format!("+{actual}"),
+            };
+            text.push_str(&cell);
+            text.push(' ');
+        }
+        text.push_str("|\n");
+    }
+    text.push_str(separator);
+    text
+}
+
+/// HTML `<table>` overlay. Cells carry one of the `match`, `wrong`, `missing`,
+/// `extra` or `empty` classes for styling, and the expected value as a tooltip
+/// when it differs
+pub fn overlay_html(diff: &GridDiff) -> String {
+    let mut html = String::from("<table class=\"sudoku-overlay\">\n");
+
+    for row in 0..SUDOKU_SIZE {
+        html.push_str("  <tr>");
+        for col in 0..SUDOKU_SIZE {
+            let (class, content, title) = match diff.get(row, col).unwrap_or(CellDiff::Empty) {
+                CellDiff::Empty => ("empty", String::new(), None),
+                CellDiff::Match(value) => ("match", value.to_string(), None),
+                CellDiff::Wrong { expected, actual } => {
+                    ("wrong", actual.to_string(), Some(expected))
+                }
+                CellDiff::Missing { expected } => ("missing", String::new(), Some(expected)),
+                CellDiff::Extra { actual } => ("extra", actual.to_string(), None),
+            };
+            // Writing into a `String` can't fail
+            let _ = match title {
+                Some(expected) => write!(
+                    html,
+                    "<td class=\"{class}\" title=\"expected {expected}\">{content}</td>"
+                ),
+                None => write!(html, "<td class=\"{class}\">{content}</td>"),
+            };
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Sudoku;
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_overlay() {
+        let solution = Sudoku::from_line(SOLUTION).unwrap();
+        let mut attempt = solution.clone();
+        attempt[(0, 1)] = 7;
+        attempt[(0, 2)] = 0;
+        let diff = solution.diff(&attempt);
+
+        let text = overlay_text(&diff);
+        assert_eq!(
+            text.lines().nth(1),
+            Some("|  5 x7  _ |  6  7  8 |  9  1  2 |")
+        );
+        assert_eq!(text.lines().count(), 13);
+
+        let html = overlay_html(&diff);
+        assert!(html.contains(
+            "<td class=\"match\">5</td><td class=\"wrong\" title=\"expected 3\">7</td>\
+             <td class=\"missing\" title=\"expected 4\"></td>"
+        ));
+        assert_eq!(html.matches("<td").count(), 81);
+    }
+}
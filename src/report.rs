@@ -0,0 +1,274 @@
+//! All the errors of one validation as a value, to compare grid states
+//! or hand them over to other tools (see the `serde` feature)
+use alloc::{vec, vec::Vec};
+
+use crate::{RuleSet, Sudoku, ValidationError};
+
+/// How bad a [`ValidationError`] is, errors sort after warnings
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Severity {
+    /// The grid breaks no rule yet, but isn't finished or isn't a proper
+    /// puzzle: [`ValidationError::Incomplete`] and [`ValidationError::MultipleSolutions`]
+    Warning,
+    /// The grid breaks a rule, every other error
+    Error,
+}
+
+impl ValidationError {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::Incomplete { .. } | Self::MultipleSolutions => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+
+    /// Cells involved in the error as 0-based `(row, column)` pairs in row-major
+    /// order, whatever order the check found them in. Errors about the whole
+    /// grid or about a cage have none
+    pub fn cells(&self) -> Vec<(usize, usize)> {
+        let mut cells = match self {
+            Self::Dublication { indexes, .. } | Self::Violation { indexes, .. } => indexes.to_vec(),
+            Self::OutOfRange { index, .. } => vec![*index],
+            _ => Vec::new(),
+        };
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct ValidationReport {
+    pub errors: Vec<ValidationError>,
+}
+
+/// Errors that changed between two reports
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct ReportDiff {
+    /// Found in the newer report only: what the edit broke
+    pub appeared: Vec<ValidationError>,
+    /// Found in the older report only: what the edit fixed
+    pub disappeared: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+    pub fn new(errors: Vec<ValidationError>) -> Self {
+        Self { errors }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Severity of the worst error, `None` for a valid grid
+    pub fn severity(&self) -> Option<Severity> {
+        self.errors.iter().map(ValidationError::severity).max()
+    }
+
+    /// Compare with the report of a later state of the grid. An error that only
+    /// changed its cells, e.g. a duplicate gaining a third cell, counts as
+    /// one disappeared and one appeared error
+    pub fn diff(&self, newer: &ValidationReport) -> ReportDiff {
+        ReportDiff {
+            appeared: missing_from(&newer.errors, &self.errors),
+            disappeared: missing_from(&self.errors, &newer.errors),
+        }
+    }
+}
+
+/// Errors of `errors` without a match in `others`, each match used once
+fn missing_from(errors: &[ValidationError], others: &[ValidationError]) -> Vec<ValidationError> {
+    let mut matched = vec![false; others.len()];
+    errors
+        .iter()
+        .filter(|error| {
+            let found = (0..others.len()).find(|i| !matched[*i] && others[*i] == **error);
+            found.map(|i| matched[i] = true).is_none()
+        })
+        .cloned()
+        .collect()
+}
+
+impl ReportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.appeared.is_empty() && self.disappeared.is_empty()
+    }
+}
+
+impl From<Result<Sudoku, Vec<ValidationError>>> for ValidationReport {
+    fn from(result: Result<Sudoku, Vec<ValidationError>>) -> Self {
+        Self::new(result.err().unwrap_or_default())
+    }
+}
+
+impl Sudoku {
+    /// Same checks as [`Sudoku::validate_against`], without consuming the grid
+    pub fn report(&self, rules: &RuleSet) -> ValidationReport {
+        ValidationReport::new(rules.check(self))
+    }
+}
+
+#[cfg(feature = "serde")]
+mod schema {
+    use alloc::{borrow::Cow, format, vec::Vec};
+
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Severity, ValidationReport};
+    use crate::{ValidationError, SCHEMA_VERSION};
+
+    #[derive(Serialize, Deserialize)]
+    struct ReportSchema<'a> {
+        schema_version: u32,
+        valid: bool,
+        errors: Vec<EntrySchema<'a>>,
+    }
+
+    /// Severity and cells are derived from the error, only written for the readers
+    #[derive(Serialize, Deserialize)]
+    struct EntrySchema<'a> {
+        severity: Severity,
+        cells: Vec<(usize, usize)>,
+        error: Cow<'a, ValidationError>,
+    }
+
+    impl Serialize for ValidationReport {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            ReportSchema {
+                schema_version: SCHEMA_VERSION,
+                valid: self.is_valid(),
+                errors: self
+                    .errors
+                    .iter()
+                    .map(|error| EntrySchema {
+                        severity: error.severity(),
+                        cells: error.cells(),
+                        error: Cow::Borrowed(error),
+                    })
+                    .collect(),
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for ValidationReport {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let schema = ReportSchema::deserialize(deserializer)?;
+            if schema.schema_version > SCHEMA_VERSION {
+                return Err(de::Error::custom(format!(
+                    "unsupported schema version {}, expected at most {SCHEMA_VERSION}",
+                    schema.schema_version
+                )));
+            }
+            Ok(Self::new(
+                schema
+                    .errors
+                    .into_iter()
+                    .map(|entry| entry.error.into_owned())
+                    .collect(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValidationErrorType, ValidationProfile, EMPTY};
+    #[cfg(feature = "serde")]
+    use alloc::format;
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_report_diff() {
+        let rules = RuleSet::standard();
+        let mut sudoku = Sudoku::from_line(SOLUTION).unwrap();
+        sudoku[(0, 0)] = EMPTY;
+        let empty = sudoku.report(&rules);
+        assert!(empty.is_valid());
+
+        sudoku[(0, 0)] = 3;
+        let broken = sudoku.report(&rules);
+        let diff = empty.diff(&broken);
+        assert_eq!(diff.appeared.len(), 3);
+        assert!(diff.disappeared.is_empty());
+        assert_eq!(broken.diff(&empty).disappeared, diff.appeared);
+
+        // Moving the conflict to another digit: the row conflict moves too
+        sudoku[(0, 0)] = 4;
+        let moved = sudoku.report(&rules);
+        let diff = broken.diff(&moved);
+        assert!(diff.appeared.contains(&ValidationError::Dublication {
+            type_: ValidationErrorType::Row(0),
+            value: 4,
+            indexes: [(0, 0), (0, 2)].into_iter().collect(),
+        }));
+        assert_eq!(diff.disappeared.len(), 3);
+
+        assert!(moved.diff(&moved).is_empty());
+        assert_eq!(
+            ValidationReport::from(sudoku.validate_profile(ValidationProfile::Partial)),
+            moved
+        );
+    }
+
+    #[test]
+    fn test_severity_and_cells() {
+        let error = ValidationError::Dublication {
+            type_: ValidationErrorType::Column(2),
+            value: 7,
+            indexes: [(8, 2), (1, 2), (4, 2)].into_iter().collect(),
+        };
+        assert_eq!(error.cells(), vec![(1, 2), (4, 2), (8, 2)]);
+        assert_eq!(error.severity(), Severity::Error);
+
+        let incomplete = ValidationError::Incomplete { empty_count: 3 };
+        assert_eq!(incomplete.severity(), Severity::Warning);
+        assert!(incomplete.cells().is_empty());
+
+        assert_eq!(ValidationReport::default().severity(), None);
+        assert_eq!(
+            ValidationReport::new(vec![incomplete.clone()]).severity(),
+            Some(Severity::Warning)
+        );
+        assert_eq!(
+            ValidationReport::new(vec![incomplete, error]).severity(),
+            Some(Severity::Error)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_report_json() {
+        let report = ValidationReport::new(vec![
+            ValidationError::OutOfRange {
+                index: (4, 2),
+                value: 12,
+            },
+            ValidationError::MultipleSolutions,
+        ]);
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            json,
+            format!(
+                concat!(
+                    r#"{{"schema_version":{},"valid":false,"errors":["#,
+                    r#"{{"severity":"error","cells":[[4,2]],"error":{{"kind":"OutOfRange","index":[4,2],"value":12}}}},"#,
+                    r#"{{"severity":"warning","cells":[],"error":{{"kind":"MultipleSolutions"}}}}]}}"#
+                ),
+                crate::SCHEMA_VERSION
+            )
+        );
+        assert_eq!(
+            serde_json::from_str::<ValidationReport>(&json).unwrap(),
+            report
+        );
+    }
+}
@@ -0,0 +1,464 @@
+//! Samurai Sudoku: five standard grids, the center one sharing each of its
+//! corner boxes with one of the four others
+//!
+//! The text format is the usual 21 by 21 layout, with `.` or `0` for empty
+//! cells and spaces where no grid is:
+//!
+//! ```text
+//! 53..7....   ..9......
+//! 6..195...   .1.......
+//! ...
+//! ```
+//!
+//! Trailing spaces may be left out
+use alloc::{vec, vec::Vec};
+use core::{fmt, ops::ControlFlow, str::FromStr};
+
+use crate::{
+    box_index,
+    candidates::ALL_CANDIDATES,
+    cell_char,
+    linked::{EachGrid, LinkedError, LinkedRuleSet, Links},
+    RuleSet, Sudoku, EMPTY, SUDOKU_SIZE,
+};
+
+pub const GRIDS_COUNT: usize = 5;
+/// Number of rows, and of columns, of the whole layout
+pub const LAYOUT_SIZE: usize = 21;
+/// Top-left cell of each grid in the layout: top-left, top-right, center,
+/// bottom-left and bottom-right
+pub const OFFSETS: [(usize, usize); GRIDS_COUNT] = [(0, 0), (0, 12), (6, 6), (12, 0), (12, 12)];
+/// Boxes shared by the outer grids and the center one, as `(grid, box)` pairs
+const SHARED_BOXES: [((usize, usize), (usize, usize)); 4] = [
+    ((0, 8), (2, 0)),
+    ((1, 6), (2, 2)),
+    ((3, 2), (2, 6)),
+    ((4, 0), (2, 8)),
+];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Samurai {
+    /// In the order of [`OFFSETS`]
+    pub grids: [Sudoku; GRIDS_COUNT],
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SamuraiParseError {
+    WrongLineCount {
+        count: usize,
+    },
+    /// A line ends before its last cell or goes past the layout, `line` counts from 1
+    WrongLineLength {
+        line: usize,
+        len: usize,
+    },
+    /// `line` and `column` count characters of the input from 1
+    WrongSymbol {
+        symbol: char,
+        line: usize,
+        column: usize,
+    },
+}
+
+/// Grids covering a cell of the layout, with the position inside each of them
+fn grids_at(row: usize, col: usize) -> impl Iterator<Item = (usize, (usize, usize))> {
+    OFFSETS
+        .into_iter()
+        .enumerate()
+        .filter_map(move |(grid, (top, left))| {
+            let position = (row.checked_sub(top)?, col.checked_sub(left)?);
+            (position.0 < SUDOKU_SIZE && position.1 < SUDOKU_SIZE).then_some((grid, position))
+        })
+}
+
+fn is_cell(row: usize, col: usize) -> bool {
+    grids_at(row, col).next().is_some()
+}
+
+impl Samurai {
+    pub fn new(grids: [Sudoku; GRIDS_COUNT]) -> Self {
+        Self { grids }
+    }
+
+    pub fn empty() -> Self {
+        Self::new(core::array::from_fn(|_| Sudoku::empty()))
+    }
+
+    /// Value at a cell of the layout, `None` where no grid is. For a shared
+    /// cell, the value of the outer grid unless only the center one is filled
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        grids_at(row, col)
+            .map(|(grid, position)| self.grids[grid][position])
+            .reduce(|value, other| match value {
+                EMPTY => other,
+                value => value,
+            })
+    }
+
+    /// Standard rules on every grid, plus equality of the shared boxes
+    pub fn rules() -> LinkedRuleSet {
+        let links = SHARED_BOXES
+            .into_iter()
+            .fold(Links::new(), |links, (outer, center)| {
+                links.share_box(outer, center)
+            });
+        LinkedRuleSet::new()
+            .with(EachGrid(RuleSet::standard()))
+            .with(links)
+    }
+
+    /// Duplicates inside of each grid and shared cells holding different values
+    pub fn validate(&self) -> Result<(), Vec<LinkedError>> {
+        Self::rules().validate(&self.grids)
+    }
+
+    /// Fill every empty cell of the five grids at once, `None` if the puzzle has
+    /// no solution or the grids disagree on a shared cell.
+    ///
+    /// When several solutions exist, the first one found is returned
+    pub fn solve(&self) -> Option<Samurai> {
+        let mut search = Search::new(self)?;
+        search
+            .run(&mut |search| ControlFlow::Break(search.to_samurai()))
+            .break_value()
+    }
+
+    /// Number of solutions, counting stops once `limit` is reached
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        let Some(mut search) = Search::new(self).filter(|_| limit > 0) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        let _ = search.run(&mut |_| {
+            count += 1;
+            match count < limit {
+                true => ControlFlow::Continue(()),
+                false => ControlFlow::Break(()),
+            }
+        });
+        count
+    }
+
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+}
+
+impl FromStr for Samurai {
+    type Err = SamuraiParseError;
+
+    fn from_str(s: &str) -> Result<Self, SamuraiParseError> {
+        use SamuraiParseError::*;
+
+        let lines = s.lines().map(str::trim_end).collect::<Vec<_>>();
+        if lines.len() != LAYOUT_SIZE {
+            return Err(WrongLineCount { count: lines.len() });
+        }
+
+        let mut samurai = Self::empty();
+        for (row, line) in lines.into_iter().enumerate() {
+            let len = line.chars().count();
+            let last_cell = (0..LAYOUT_SIZE).rev().find(|col| is_cell(row, *col));
+            if len > LAYOUT_SIZE || last_cell.is_some_and(|col| len <= col) {
+                return Err(WrongLineLength { line: row + 1, len });
+            }
+
+            for (col, symbol) in line.chars().enumerate() {
+                let value = match (is_cell(row, col), symbol) {
+                    (false, ' ') => continue,
+                    (true, '.') => EMPTY,
+                    (true, '0'..='9') => symbol as u8 - b'0',
+                    _ => {
+                        return Err(WrongSymbol {
+                            symbol,
+                            line: row + 1,
+                            column: col + 1,
+                        })
+                    }
+                };
+                for (grid, position) in grids_at(row, col) {
+                    samurai.grids[grid][position] = value;
+                }
+            }
+        }
+        Ok(samurai)
+    }
+}
+
+impl fmt::Display for Samurai {
+    /// The layout [`Samurai::from_str`] reads, empty cells as `.` and values
+    /// out of range as `?`, as in [`Sudoku::to_line`]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (0..LAYOUT_SIZE).try_for_each(|row| {
+            let mut line = (0..LAYOUT_SIZE)
+                .map(|col| self.get(row, col).map_or(' ', cell_char))
+                .collect::<alloc::string::String>();
+            line.truncate(line.trim_end().len());
+            writeln!(f, "{line}")
+        })
+    }
+}
+
+/// Rows, columns and boxes of one grid
+const UNITS_PER_GRID: usize = 3 * SUDOKU_SIZE;
+/// A shared cell is in the row, column and box of two grids
+const MAX_UNITS: usize = 6;
+
+/// Backtracking over the cells of the whole layout, so a digit placed in a
+/// shared box immediately constrains both grids
+struct Search {
+    /// Layout position of every cell
+    positions: Vec<(usize, usize)>,
+    values: Vec<u8>,
+    /// Units of every cell, padded by repeating the first one
+    units: Vec<[usize; MAX_UNITS]>,
+    /// Bitmask of the digits used in each unit
+    used: Vec<u16>,
+}
+
+impl Search {
+    /// Returns `None` if a value is out of range, if shared cells disagree
+    /// or if the givens already contradict each other
+    fn new(samurai: &Samurai) -> Option<Self> {
+        let mut search = Self {
+            positions: Vec::new(),
+            values: Vec::new(),
+            units: Vec::new(),
+            used: vec![0; GRIDS_COUNT * UNITS_PER_GRID],
+        };
+
+        for row in 0..LAYOUT_SIZE {
+            for col in 0..LAYOUT_SIZE {
+                let mut units = Vec::new();
+                let mut value = EMPTY;
+                for (grid, (local_row, local_col)) in grids_at(row, col) {
+                    let base = grid * UNITS_PER_GRID;
+                    units.extend([
+                        base + local_row,
+                        base + SUDOKU_SIZE + local_col,
+                        base + 2 * SUDOKU_SIZE + box_index(local_row, local_col),
+                    ]);
+                    match (value, samurai.grids[grid][(local_row, local_col)]) {
+                        (_, EMPTY) => {}
+                        (EMPTY, other) => value = other,
+                        (value, other) if value != other => return None,
+                        _ => {}
+                    }
+                }
+                let Some(&first) = units.first() else {
+                    continue;
+                };
+
+                let index = search.positions.len();
+                search.positions.push((row, col));
+                search.values.push(EMPTY);
+                search.units.push(core::array::from_fn(|i| {
+                    units.get(i).copied().unwrap_or(first)
+                }));
+                if value != EMPTY {
+                    if value as usize > SUDOKU_SIZE
+                        || search.candidates(index) & (1 << (value - 1)) == 0
+                    {
+                        return None;
+                    }
+                    search.place(index, value);
+                }
+            }
+        }
+        Some(search)
+    }
+
+    fn candidates(&self, index: usize) -> u16 {
+        let used = self.units[index]
+            .iter()
+            .fold(0, |used, unit| used | self.used[*unit]);
+        !used & ALL_CANDIDATES
+    }
+
+    fn toggle(&mut self, index: usize, value: u8) {
+        let bit = 1 << (value - 1);
+        let mut units = self.units[index];
+        // Padding repeats a unit, which must only be toggled once
+        units.sort_unstable();
+        let mut previous = None;
+        for unit in units {
+            if previous != Some(unit) {
+                self.used[unit] ^= bit;
+            }
+            previous = Some(unit);
+        }
+    }
+
+    fn place(&mut self, index: usize, value: u8) {
+        self.values[index] = value;
+        self.toggle(index, value);
+    }
+
+    fn remove(&mut self, index: usize) {
+        let value = self.values[index];
+        self.values[index] = EMPTY;
+        self.toggle(index, value);
+    }
+
+    /// Empty cell with the fewest candidates, `None` when the layout is full
+    fn most_constrained(&self) -> Option<(usize, u16)> {
+        let mut best: Option<(usize, u16)> = None;
+        for index in (0..self.values.len()).filter(|&index| self.values[index] == EMPTY) {
+            let candidates = self.candidates(index);
+            if best.is_none_or(|(_, best)| candidates.count_ones() < best.count_ones()) {
+                best = Some((index, candidates));
+                if candidates.count_ones() <= 1 {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    fn run<B, F>(&mut self, on_solution: &mut F) -> ControlFlow<B>
+    where
+        F: FnMut(&Self) -> ControlFlow<B>,
+    {
+        let Some((index, mut candidates)) = self.most_constrained() else {
+            return on_solution(self);
+        };
+
+        while candidates != 0 {
+            let value = candidates.trailing_zeros() as u8 + 1;
+            candidates &= candidates - 1;
+
+            self.place(index, value);
+            let flow = self.run(on_solution);
+            self.remove(index);
+            flow?;
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn to_samurai(&self) -> Samurai {
+        let mut samurai = Samurai::empty();
+        for (&(row, col), &value) in self.positions.iter().zip(&self.values) {
+            for (grid, position) in grids_at(row, col) {
+                samurai.grids[grid][position] = value;
+            }
+        }
+        samurai
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    /// Every other cell of a solved layout, checkerboard-like
+    fn puzzle() -> Samurai {
+        let mut puzzle = Samurai::empty().solve().unwrap();
+        for (grid, sudoku) in puzzle.grids.iter_mut().enumerate() {
+            let (top, left) = OFFSETS[grid];
+            for row in 0..SUDOKU_SIZE {
+                for col in 0..SUDOKU_SIZE {
+                    if (top + row + left + col) % 2 == 1 {
+                        sudoku[(row, col)] = EMPTY;
+                    }
+                }
+            }
+        }
+        puzzle
+    }
+
+    #[test]
+    fn test_solve() {
+        let solution = Samurai::empty().solve().unwrap();
+        assert_eq!(solution.validate(), Ok(()));
+        assert!(solution
+            .grids
+            .iter()
+            .all(|grid| grid.cells().all(|(_, value)| value != EMPTY)));
+        assert!(solution.has_unique_solution());
+
+        let puzzle = puzzle();
+        assert_eq!(puzzle.validate(), Ok(()));
+        let solved = puzzle.solve().unwrap();
+        assert_eq!(solved.validate(), Ok(()));
+        assert!((0..LAYOUT_SIZE).all(|row| (0..LAYOUT_SIZE).all(|col| {
+            puzzle.get(row, col) == Some(EMPTY) || puzzle.get(row, col) == solved.get(row, col)
+        })));
+    }
+
+    #[test]
+    fn test_shared_cells() {
+        let mut samurai = Samurai::empty().solve().unwrap();
+        // Bottom-right cell of the top-left grid is the top-left one of the center grid
+        assert_eq!(samurai.grids[0][(8, 8)], samurai.grids[2][(2, 2)]);
+
+        let value = samurai.grids[2][(2, 2)];
+        samurai.grids[2][(2, 2)] = value % 9 + 1;
+        assert!(samurai
+            .validate()
+            .unwrap_err()
+            .contains(&LinkedError::Mismatch {
+                cells: [(0, (8, 8)), (2, (2, 2))],
+                values: [value, value % 9 + 1],
+            }));
+        assert_eq!(samurai.solve(), None);
+        assert_eq!(samurai.count_solutions(10), 0);
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let puzzle = puzzle();
+        let text = puzzle.to_string();
+        assert_eq!(text.lines().count(), LAYOUT_SIZE);
+        assert_eq!(text.lines().nth(9).unwrap().len(), 15);
+        assert_eq!(text.parse::<Samurai>(), Ok(puzzle));
+    }
+
+    #[test]
+    fn test_display_out_of_range() {
+        let mut samurai = Samurai::empty();
+        samurai.grids[0][(0, 0)] = 12;
+        samurai.grids[4][(8, 8)] = 250;
+
+        let text = samurai.to_string();
+        assert!(text.starts_with("?........   ........."));
+        assert!(text.lines().last().unwrap().ends_with("........?"));
+        assert_eq!(
+            text.parse::<Samurai>(),
+            Err(SamuraiParseError::WrongSymbol {
+                symbol: '?',
+                line: 1,
+                column: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let text = puzzle().to_string();
+        assert_eq!(
+            "123".parse::<Samurai>(),
+            Err(SamuraiParseError::WrongLineCount { count: 1 })
+        );
+
+        let mut lines = text.lines().map(str::to_owned).collect::<Vec<_>>();
+        lines[0].replace_range(9..10, "x");
+        assert_eq!(
+            lines.join("\n").parse::<Samurai>(),
+            Err(SamuraiParseError::WrongSymbol {
+                symbol: 'x',
+                line: 1,
+                column: 10
+            })
+        );
+
+        let mut lines = text.lines().map(str::to_owned).collect::<Vec<_>>();
+        lines[10].truncate(10);
+        assert_eq!(
+            lines.join("\n").parse::<Samurai>(),
+            Err(SamuraiParseError::WrongLineLength { line: 11, len: 10 })
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Serde schema of the stable core, versioned by [`SCHEMA_VERSION`]
+//!
+//! A [`Sudoku`] is an object with the version and the grid in the one-line
+//! format (see [`Sudoku::from_line`]):
+//!
+//! ```json
+//! { "schema_version": 1, "grid": "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79" }
+//! ```
+//!
+//! A [`ValidationError`](crate::ValidationError) is an object tagged by `kind`,
+//! cells are `[row, column]` pairs and units are tagged by their type:
+//!
+//! ```json
+//! { "kind": "Duplication", "unit": { "Row": 0 }, "value": 5, "cells": [[0, 0], [0, 3]] }
+//! { "kind": "OutOfRange", "index": [4, 2], "value": 12 }
+//! { "kind": "Incomplete", "empty_count": 51 }
+//! { "kind": "Unsolvable" }
+//! { "kind": "MultipleSolutions" }
+//! { "kind": "CageSum", "cage": 3, "expected": 17, "actual": 19 }
+//! { "kind": "Violation", "rule": "anti-knight", "cells": [[0, 0], [1, 2]] }
+//! ```
+//!
+//! A [`ValidationReport`](crate::ValidationReport) adds the severity and the
+//! row-major sorted cells of each error, both ignored when reading it back:
+//!
+//! ```json
+//! { "schema_version": 1, "valid": false, "errors": [
+//!     { "severity": "warning", "cells": [], "error": { "kind": "Incomplete", "empty_count": 51 } }
+//! ] }
+//! ```
+//!
+//! Only `Sudoku` and `ValidationReport` are versioned documents. An error, or the
+//! list returned by [`Sudoku::validate`], has no `schema_version` of its own and
+//! is meant to be embedded in one of them: to store or send errors on their own,
+//! wrap them with [`ValidationReport::new`](crate::ValidationReport::new) so
+//! readers can tell which version they got.
+//!
+//! Fields are only ever added within a version, a change to existing ones bumps
+//! [`SCHEMA_VERSION`]. Documents from a newer version are rejected
+use alloc::{borrow::Cow, format};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Sudoku;
+
+/// Version written into, and accepted from, serialized [`Sudoku`] and
+/// [`ValidationReport`](crate::ValidationReport) documents
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct SudokuSchema<'a> {
+    schema_version: u32,
+    #[serde(borrow)]
+    grid: Cow<'a, str>,
+}
+
+impl Serialize for Sudoku {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SudokuSchema {
+            schema_version: SCHEMA_VERSION,
+            grid: Cow::Owned(self.to_line()),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Sudoku {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let schema = SudokuSchema::deserialize(deserializer)?;
+        if schema.schema_version > SCHEMA_VERSION {
+            return Err(de::Error::custom(format!(
+                "unsupported schema version {}, expected at most {SCHEMA_VERSION}",
+                schema.schema_version
+            )));
+        }
+        Sudoku::from_line(&schema.grid)
+            .map_err(|err| de::Error::custom(format!("invalid grid: {err:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ValidationError, ValidationErrorType, ValidationReport};
+    use alloc::vec;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_sudoku_schema() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let json = serde_json::to_string(&sudoku).unwrap();
+        assert_eq!(
+            json,
+            format!("{{\"schema_version\":{SCHEMA_VERSION},\"grid\":\"{PUZZLE}\"}}")
+        );
+        assert_eq!(serde_json::from_str::<Sudoku>(&json).unwrap(), sudoku);
+
+        let newer = json.replace(
+            &format!(":{SCHEMA_VERSION},"),
+            &format!(":{},", SCHEMA_VERSION + 1),
+        );
+        assert!(serde_json::from_str::<Sudoku>(&newer).is_err());
+    }
+
+    #[test]
+    fn test_validation_error_schema() {
+        let error = ValidationError::Dublication {
+            type_: ValidationErrorType::Row(0),
+            value: 5,
+            indexes: [(0, 0), (0, 3)].into_iter().collect(),
+        };
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(
+            json,
+            r#"{"kind":"Duplication","unit":{"Row":0},"value":5,"cells":[[0,0],[0,3]]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ValidationError>(&json).unwrap(),
+            error
+        );
+
+        let report = serde_json::to_string(&ValidationReport::new(vec![error])).unwrap();
+        assert!(report.starts_with(&format!("{{\"schema_version\":{SCHEMA_VERSION},")));
+        assert!(report.contains(&json));
+    }
+}
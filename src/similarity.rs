@@ -0,0 +1,108 @@
+use crate::{Sudoku, EMPTY, SUDOKU_SIZE};
+
+impl Sudoku {
+    /// How alike two puzzles are, from `0.0` (unrelated) to `1.0` (identical up
+    /// to a [`Transformation`](crate::transform::Transformation)).
+    ///
+    /// Both puzzles are first aligned through the transformation bringing their
+    /// solution to its [`canonical form`](Sudoku::canonical_form), or the puzzle
+    /// itself when it has no solution. The score is then the mean of
+    /// - clue overlap: aligned clues with the same position and value, over all the
+    ///   positions holding a clue in either puzzle
+    /// - solution agreement: share of cells where the aligned solutions match
+    ///   (`0.0` if either puzzle has no solution)
+    ///
+    /// Two puzzles that differ by a clue or two share the solution, so they score
+    /// close to `1.0` even though they are not equivalent
+    pub fn similarity(&self, other: &Sudoku) -> f32 {
+        let (solution, other_solution) = match (self.solve(), other.solve()) {
+            (Some(solution), Some(other_solution)) => (solution, other_solution),
+            _ => (self.clone(), other.clone()),
+        };
+        let solved = solution.cells().all(|(_, value)| value != EMPTY)
+            && other_solution.cells().all(|(_, value)| value != EMPTY);
+
+        let alignment = solution.canonical_transformation();
+        let other_alignment = other_solution.canonical_transformation();
+
+        let (mut shared, mut total) = (0, 0);
+        for ((_, value), (_, other)) in alignment
+            .apply(self)
+            .cells()
+            .zip(other_alignment.apply(other).cells())
+        {
+            if value != EMPTY || other != EMPTY {
+                total += 1;
+                shared += usize::from(value == other);
+            }
+        }
+
+        let clue_overlap = match total {
+            0 => 1.0,
+            total => shared as f32 / total as f32,
+        };
+        let solution_agreement = match solved {
+            true => {
+                let agreement = alignment
+                    .apply(&solution)
+                    .cells()
+                    .zip(other_alignment.apply(&other_solution).cells())
+                    .filter(|((_, value), (_, other))| value == other)
+                    .count();
+                agreement as f32 / (SUDOKU_SIZE * SUDOKU_SIZE) as f32
+            }
+            false => 0.0,
+        };
+
+        (clue_overlap + solution_agreement) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::Transformation;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const OTHER_PUZZLE: &str =
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..";
+
+    #[test]
+    fn test_similarity() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        assert_eq!(puzzle.similarity(&puzzle), 1.0);
+
+        let mut relabeled = puzzle.clone();
+        relabeled
+            .grid
+            .iter_mut()
+            .flatten()
+            .filter(|value| **value != EMPTY)
+            .for_each(|value| *value = *value % 9 + 1);
+        let transposed = Sudoku {
+            grid: core::array::from_fn(|row| core::array::from_fn(|col| relabeled.grid[col][row])),
+        };
+        assert_eq!(puzzle.similarity(&transposed), 1.0);
+        for seed in 0..4 {
+            let transformed = Transformation::from_seed(seed).apply(&puzzle);
+            assert_eq!(puzzle.similarity(&transformed), 1.0);
+        }
+        let band_swapped = Transformation::permute_rows([1, 0, 2], [[0, 1, 2]; 3])
+            .unwrap()
+            .apply(&puzzle);
+        assert_eq!(puzzle.similarity(&band_swapped), 1.0);
+
+        let mut near_duplicate = puzzle.clone();
+        near_duplicate[(0, 2)] = 4;
+        let near = puzzle.similarity(&near_duplicate);
+        assert!(near > 0.95 && near < 1.0, "{near}");
+        assert_eq!(
+            puzzle.similarity(&Transformation::from_seed(5).apply(&near_duplicate)),
+            near
+        );
+
+        let unrelated = puzzle.similarity(&Sudoku::from_line(OTHER_PUZZLE).unwrap());
+        assert!(unrelated < 0.5, "{unrelated}");
+    }
+}
@@ -0,0 +1,708 @@
+//! Backtracking and dancing links solvers behind the [`Solver`] trait,
+//! and a streaming front-end for line-format puzzle collections.
+//!
+//! Only the [`Sudoku`] methods and [`Solutions`] are part of the stable core,
+//! the rest of this module needs the `unstable` feature
+use alloc::vec::Vec;
+use core::ops::ControlFlow;
+#[cfg(all(feature = "std", feature = "unstable"))]
+use std::io::{self, BufRead, Write};
+
+#[cfg(feature = "unstable")]
+use crate::candidates::CandidateGrid;
+#[cfg(feature = "unstable")]
+pub use crate::dlx::DancingLinks;
+use crate::{
+    box_index, candidates::ALL_CANDIDATES, extra_region_cell, Rules, Sudoku, EMPTY, SUDOKU_SIZE,
+};
+
+pub(crate) const CELLS_COUNT: usize = SUDOKU_SIZE * SUDOKU_SIZE;
+/// Both diagonals, then the four regions of Hyper-Sudoku
+const EXTRA_UNITS_COUNT: usize = 6;
+
+/// Search state: the flattened grid plus one bitmask of used digits per house,
+/// so candidates of a cell are a couple of bit operations away
+#[derive(Clone)]
+pub(crate) struct Backtracker {
+    cells: [u8; CELLS_COUNT],
+    rows: [u16; SUDOKU_SIZE],
+    columns: [u16; SUDOKU_SIZE],
+    boxes: [u16; SUDOKU_SIZE],
+    /// Used digits of the units added by [`Rules`]
+    extras: [u16; EXTRA_UNITS_COUNT],
+    /// Bit `i` set when the cell belongs to the extra unit `i`, all zero for
+    /// standard rules so they cost nothing
+    extra_units: [u8; CELLS_COUNT],
+    /// Number of times the search had to pick between several candidates
+    guesses: usize,
+}
+
+/// Bits of the extra units each cell belongs to under `rules`
+fn extra_units(rules: Rules) -> [u8; CELLS_COUNT] {
+    let mut units = [0; CELLS_COUNT];
+    if rules.diagonals {
+        for i in 0..SUDOKU_SIZE {
+            units[i * SUDOKU_SIZE + i] |= 1;
+            units[i * SUDOKU_SIZE + SUDOKU_SIZE - 1 - i] |= 1 << 1;
+        }
+    }
+    if rules.extra_regions {
+        for region in 0..4 {
+            for index in 0..SUDOKU_SIZE {
+                let (row, col) = extra_region_cell(region, index);
+                units[row * SUDOKU_SIZE + col] |= 1 << (2 + region);
+            }
+        }
+    }
+    units
+}
+
+/// Indexes of the bits set in `units`
+fn unit_indexes(units: u8) -> impl Iterator<Item = usize> {
+    (0..EXTRA_UNITS_COUNT).filter(move |i| units & (1 << i) != 0)
+}
+
+impl Backtracker {
+    /// Returns `None` if the givens already contradict each other
+    pub(crate) fn new(sudoku: &Sudoku) -> Option<Self> {
+        Self::with_rules(sudoku, Rules::STANDARD)
+    }
+
+    /// Same as [`Backtracker::new`] with the additional units of `rules`
+    pub(crate) fn with_rules(sudoku: &Sudoku, rules: Rules) -> Option<Self> {
+        let mut state = Self {
+            cells: [EMPTY; CELLS_COUNT],
+            rows: [0; SUDOKU_SIZE],
+            columns: [0; SUDOKU_SIZE],
+            boxes: [0; SUDOKU_SIZE],
+            extras: [0; EXTRA_UNITS_COUNT],
+            extra_units: extra_units(rules),
+            guesses: 0,
+        };
+
+        for (row, values) in sudoku.grid.iter().enumerate() {
+            for (col, &value) in values.iter().enumerate() {
+                if value == EMPTY {
+                    continue;
+                }
+                if value as usize > SUDOKU_SIZE {
+                    return None;
+                }
+                let index = row * SUDOKU_SIZE + col;
+                if state.candidates(index) & (1 << (value - 1)) == 0 {
+                    return None;
+                }
+                state.place(index, value);
+            }
+        }
+
+        Some(state)
+    }
+
+    pub(crate) fn candidates(&self, index: usize) -> u16 {
+        let (row, col) = (index / SUDOKU_SIZE, index % SUDOKU_SIZE);
+        let mut used = self.rows[row] | self.columns[col] | self.boxes[box_index(row, col)];
+        if self.extra_units[index] != 0 {
+            unit_indexes(self.extra_units[index]).for_each(|unit| used |= self.extras[unit]);
+        }
+        !used & ALL_CANDIDATES
+    }
+
+    fn toggle(&mut self, index: usize, value: u8) {
+        let (row, col) = (index / SUDOKU_SIZE, index % SUDOKU_SIZE);
+        let bit = 1 << (value - 1);
+        self.rows[row] ^= bit;
+        self.columns[col] ^= bit;
+        self.boxes[box_index(row, col)] ^= bit;
+        if self.extra_units[index] != 0 {
+            unit_indexes(self.extra_units[index]).for_each(|unit| self.extras[unit] ^= bit);
+        }
+    }
+
+    pub(crate) fn place(&mut self, index: usize, value: u8) {
+        self.cells[index] = value;
+        self.toggle(index, value);
+    }
+
+    pub(crate) fn remove(&mut self, index: usize) {
+        let value = self.cells[index];
+        self.cells[index] = EMPTY;
+        self.toggle(index, value);
+    }
+
+    /// Empty cell with the fewest candidates, `None` when the grid is full
+    pub(crate) fn most_constrained(&self) -> Option<(usize, u16)> {
+        let mut best: Option<(usize, u16)> = None;
+        for index in (0..CELLS_COUNT).filter(|&index| self.cells[index] == EMPTY) {
+            let candidates = self.candidates(index);
+            if best.is_none_or(|(_, best)| candidates.count_ones() < best.count_ones()) {
+                best = Some((index, candidates));
+                if candidates.count_ones() <= 1 {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// Depth-first search calling `on_solution` for every complete grid,
+    /// stops as soon as the callback breaks
+    pub(crate) fn search<B, F>(&mut self, on_solution: &mut F) -> ControlFlow<B>
+    where
+        F: FnMut(&Self) -> ControlFlow<B>,
+    {
+        let Some((index, mut candidates)) = self.most_constrained() else {
+            return on_solution(self);
+        };
+        if candidates.count_ones() > 1 {
+            self.guesses += 1;
+        }
+
+        while candidates != 0 {
+            let value = candidates.trailing_zeros() as u8 + 1;
+            candidates &= candidates - 1;
+
+            self.place(index, value);
+            let flow = self.search(on_solution);
+            self.remove(index);
+            flow?;
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    pub(crate) fn is_empty(&self, index: usize) -> bool {
+        self.cells[index] == EMPTY
+    }
+
+    #[cfg(all(feature = "unstable", feature = "std"))]
+    pub(crate) fn guesses(&self) -> usize {
+        self.guesses
+    }
+
+    #[cfg(feature = "unstable")]
+    fn to_candidates(&self) -> CandidateGrid {
+        let mut candidates = [[0; SUDOKU_SIZE]; SUDOKU_SIZE];
+        candidates
+            .iter_mut()
+            .flatten()
+            .enumerate()
+            .filter(|(index, _)| self.is_empty(*index))
+            .for_each(|(index, cell)| *cell = self.candidates(index));
+        CandidateGrid {
+            sudoku: self.to_sudoku(),
+            candidates,
+        }
+    }
+
+    pub(crate) fn to_sudoku(&self) -> Sudoku {
+        let mut grid = [[EMPTY; SUDOKU_SIZE]; SUDOKU_SIZE];
+        grid.iter_mut()
+            .flatten()
+            .zip(self.cells)
+            .for_each(|(cell, value)| *cell = value);
+        Sudoku { grid }
+    }
+}
+
+impl Sudoku {
+    /// Fill every empty cell, returns `None` if the puzzle has no solution.
+    ///
+    /// When several solutions exist, the first one found is returned
+    pub fn solve(&self) -> Option<Sudoku> {
+        self.solve_with(Rules::STANDARD)
+    }
+
+    /// Same as [`Sudoku::solve`] for a variant: the solution also has every
+    /// digit once in each of the additional units of `rules`
+    pub fn solve_with(&self, rules: Rules) -> Option<Sudoku> {
+        Backtracker::with_rules(self, rules)?
+            .search(&mut |state| ControlFlow::Break(state.to_sudoku()))
+            .break_value()
+    }
+
+    /// Number of solutions, counting stops once `limit` is reached
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.count_solutions_with(Rules::STANDARD, limit)
+    }
+
+    /// Same as [`Sudoku::count_solutions`] for the variant of `rules`
+    pub fn count_solutions_with(&self, rules: Rules, limit: usize) -> usize {
+        let Some(mut state) = Backtracker::with_rules(self, rules).filter(|_| limit > 0) else {
+            return 0;
+        };
+
+        let mut count = 0;
+        let _ = state.search(&mut |_| {
+            count += 1;
+            match count < limit {
+                true => ControlFlow::Continue(()),
+                false => ControlFlow::Break(()),
+            }
+        });
+        count
+    }
+
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    /// Lazily enumerate every solution.
+    ///
+    /// The order is fixed and does not depend on the platform: the search always
+    /// fills the empty cell with the fewest candidates first (ties go to the first
+    /// cell in row-major order) and tries its candidates in ascending order,
+    /// so the first item is the grid returned by [`Sudoku::solve`]
+    pub fn solutions(&self) -> Solutions {
+        Solutions {
+            state: Backtracker::new(self),
+            stack: Vec::new(),
+            descend: true,
+        }
+    }
+
+    #[cfg(feature = "unstable")]
+    /// Every move of [`Sudoku::solve`], in the order the search makes them, to
+    /// replay the solving process. Stops once the grid is full, or after the
+    /// last backtrack when the puzzle has no solution
+    pub fn solve_steps(&self) -> SolveSteps {
+        SolveSteps {
+            state: Backtracker::new(self),
+            stack: Vec::new(),
+            descend: true,
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+/// A solving algorithm. Backends agree on whether a puzzle is solvable and on
+/// the number of solutions, but may find several solutions in a different order
+pub trait Solver {
+    /// Fill every empty cell, `None` if the puzzle has no solution
+    fn solve(&self, sudoku: &Sudoku) -> Option<Sudoku>;
+
+    /// Number of solutions, counting stops once `limit` is reached
+    fn count_solutions(&self, sudoku: &Sudoku, limit: usize) -> usize;
+
+    fn has_unique_solution(&self, sudoku: &Sudoku) -> bool {
+        self.count_solutions(sudoku, 2) == 1
+    }
+}
+
+#[cfg(feature = "unstable")]
+/// The backtracking search behind [`Sudoku::solve`]: fast on puzzles with
+/// few solutions and no setup cost
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Backtracking;
+
+#[cfg(feature = "unstable")]
+impl Solver for Backtracking {
+    fn solve(&self, sudoku: &Sudoku) -> Option<Sudoku> {
+        sudoku.solve()
+    }
+
+    fn count_solutions(&self, sudoku: &Sudoku, limit: usize) -> usize {
+        sudoku.count_solutions(limit)
+    }
+}
+
+#[cfg(feature = "unstable")]
+/// Choice of a [`Solver`] at runtime
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SolverBackend {
+    /// See [`Backtracking`]
+    #[default]
+    Backtracking,
+    /// Algorithm X on dancing links, see [`DancingLinks`]. Building the links
+    /// costs more than solving an easy puzzle, but counting or enumerating
+    /// solutions of hard and ambiguous puzzles is faster
+    Dlx,
+}
+
+#[cfg(feature = "unstable")]
+impl Solver for SolverBackend {
+    fn solve(&self, sudoku: &Sudoku) -> Option<Sudoku> {
+        match self {
+            Self::Backtracking => Backtracking.solve(sudoku),
+            Self::Dlx => DancingLinks.solve(sudoku),
+        }
+    }
+
+    fn count_solutions(&self, sudoku: &Sudoku, limit: usize) -> usize {
+        match self {
+            Self::Backtracking => Backtracking.count_solutions(sudoku, limit),
+            Self::Dlx => DancingLinks.count_solutions(sudoku, limit),
+        }
+    }
+}
+
+/// Iterator over the solutions of a puzzle, see [`Sudoku::solutions`]
+#[derive(Clone)]
+pub struct Solutions {
+    /// `None` once the search space is exhausted
+    state: Option<Backtracker>,
+    /// Guessed cells with the candidates not tried yet
+    stack: Vec<(usize, u16)>,
+    /// Whether the next step picks a new cell instead of retrying the last one
+    descend: bool,
+}
+
+impl Iterator for Solutions {
+    type Item = Sudoku;
+
+    fn next(&mut self) -> Option<Sudoku> {
+        let state = self.state.as_mut()?;
+        loop {
+            if self.descend {
+                match state.most_constrained() {
+                    Some(cell) => self.stack.push(cell),
+                    None => {
+                        self.descend = false;
+                        return Some(state.to_sudoku());
+                    }
+                }
+            }
+
+            let Some((index, candidates)) = self.stack.last_mut() else {
+                self.state = None;
+                return None;
+            };
+            if !state.is_empty(*index) {
+                state.remove(*index);
+            }
+            if *candidates == 0 {
+                self.stack.pop();
+                self.descend = false;
+                continue;
+            }
+
+            let value = candidates.trailing_zeros() as u8 + 1;
+            *candidates &= *candidates - 1;
+            state.place(*index, value);
+            self.descend = true;
+        }
+    }
+}
+
+#[cfg(feature = "unstable")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepKind {
+    /// The cell had a single candidate left
+    Deduction,
+    /// One of several candidates of the cell, the next ones are tried if it fails
+    Guess,
+    /// The value is removed again since it led to a dead end
+    Backtrack,
+}
+
+#[cfg(feature = "unstable")]
+/// One move of the search, see [`Sudoku::solve_steps`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolveStep {
+    pub position: (usize, usize),
+    /// Digit placed, or removed for [`StepKind::Backtrack`]
+    pub value: u8,
+    pub kind: StepKind,
+    /// Grid and candidates right after the move
+    pub candidates: CandidateGrid,
+}
+
+#[cfg(feature = "unstable")]
+/// Iterator over the moves of the search, see [`Sudoku::solve_steps`]
+#[derive(Clone)]
+pub struct SolveSteps {
+    /// `None` once the grid is solved or the search space is exhausted
+    state: Option<Backtracker>,
+    /// Cells being tried with the candidates not tried yet, and whether they
+    /// had a single candidate to begin with
+    stack: Vec<(usize, u16, bool)>,
+    /// Whether the next step picks a new cell instead of retrying the last one
+    descend: bool,
+}
+
+#[cfg(feature = "unstable")]
+impl Iterator for SolveSteps {
+    type Item = SolveStep;
+
+    fn next(&mut self) -> Option<SolveStep> {
+        let state = self.state.as_mut()?;
+        loop {
+            if self.descend {
+                match state.most_constrained() {
+                    Some((index, candidates)) => {
+                        self.stack
+                            .push((index, candidates, candidates.count_ones() == 1));
+                    }
+                    None => {
+                        self.state = None;
+                        return None;
+                    }
+                }
+            }
+
+            let Some((index, candidates, forced)) = self.stack.last_mut() else {
+                self.state = None;
+                return None;
+            };
+            let (index, forced) = (*index, *forced);
+            let (value, kind) = if !state.is_empty(index) {
+                let value = state.cells[index];
+                state.remove(index);
+                self.descend = false;
+                (value, StepKind::Backtrack)
+            } else if *candidates == 0 {
+                self.stack.pop();
+                self.descend = false;
+                continue;
+            } else {
+                let value = candidates.trailing_zeros() as u8 + 1;
+                *candidates &= *candidates - 1;
+                state.place(index, value);
+                self.descend = true;
+                match forced {
+                    true => (value, StepKind::Deduction),
+                    false => (value, StepKind::Guess),
+                }
+            };
+
+            return Some(SolveStep {
+                position: (index / SUDOKU_SIZE, index % SUDOKU_SIZE),
+                value,
+                kind,
+                candidates: state.to_candidates(),
+            });
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "unstable"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamConfig {
+    /// Written instead of a solution for lines that can't be parsed or solved
+    pub error_token: String,
+}
+
+#[cfg(all(feature = "std", feature = "unstable"))]
+impl Default for StreamConfig {
+    fn default() -> Self {
+        Self {
+            error_token: String::from("error"),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", feature = "unstable"))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StreamSummary {
+    pub solved: usize,
+    pub failed: usize,
+}
+
+/// Solve a stream of one-line puzzles (see [`Sudoku::from_line`]), writing one
+/// line per puzzle: either the solution or [`StreamConfig::error_token`].
+///
+/// Input is consumed line by line, so it works on collections of any size.
+/// Blank lines and `#` comments are skipped and produce no output
+#[cfg(all(feature = "std", feature = "unstable"))]
+pub fn solve_stream<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    config: &StreamConfig,
+) -> io::Result<StreamSummary> {
+    let mut summary = StreamSummary::default();
+    let mut line = String::new();
+    let mut output = [b'\n'; CELLS_COUNT + 1];
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let puzzle = line.trim_end();
+        if puzzle.is_empty() || puzzle.starts_with('#') {
+            continue;
+        }
+
+        match Sudoku::from_line(puzzle)
+            .ok()
+            .and_then(|sudoku| sudoku.solve())
+        {
+            Some(solution) => {
+                output
+                    .iter_mut()
+                    .zip(solution.grid.iter().flatten())
+                    .for_each(|(byte, value)| *byte = b'0' + value);
+                writer.write_all(&output)?;
+                summary.solved += 1;
+            }
+            None => {
+                writeln!(writer, "{}", config.error_token)?;
+                summary.failed += 1;
+            }
+        }
+    }
+
+    writer.flush()?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_solve() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let solution = sudoku.solve().unwrap();
+
+        assert_eq!(solution.to_line(), SOLUTION);
+        assert!(solution.validate().is_ok());
+    }
+
+    #[test]
+    fn test_solve_contradiction() {
+        let mut line = PUZZLE.to_owned();
+        line.replace_range(1..2, "5");
+
+        assert_eq!(Sudoku::from_line(&line).unwrap().solve(), None);
+    }
+
+    #[test]
+    fn test_count_solutions() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        assert!(sudoku.has_unique_solution());
+
+        let mut line = PUZZLE.to_owned();
+        line.replace_range(0..2, "..");
+        let sudoku = Sudoku::from_line(&line).unwrap();
+        assert_eq!(sudoku.count_solutions(usize::MAX), 2);
+        assert_eq!(sudoku.count_solutions(1), 1);
+        assert!(!sudoku.has_unique_solution());
+    }
+
+    #[test]
+    fn test_solve_with_variants() {
+        let empty = Sudoku::empty();
+        for rules in [
+            Rules::X_SUDOKU,
+            Rules::HYPER,
+            Rules::X_SUDOKU | Rules::HYPER,
+        ] {
+            let solution = empty.solve_with(rules).unwrap();
+            assert!(solution.clone().validate_with(rules).is_ok());
+            assert!(solution.cells().all(|(_, value)| value != EMPTY));
+        }
+
+        // Clues only make sense with the extra regions once enough are removed
+        let solution = empty.solve_with(Rules::HYPER).unwrap();
+        let mut puzzle = solution.clone();
+        for (position, _) in solution.cells() {
+            let value = puzzle[position];
+            puzzle[position] = EMPTY;
+            if puzzle.count_solutions_with(Rules::HYPER, 2) != 1 {
+                puzzle[position] = value;
+            }
+        }
+        assert_eq!(puzzle.solve_with(Rules::HYPER), Some(solution));
+        assert_eq!(puzzle.count_solutions(2), 2);
+    }
+
+    #[test]
+    fn test_solutions_order() {
+        let mut line = PUZZLE.to_owned();
+        line.replace_range(0..2, "..");
+        let sudoku = Sudoku::from_line(&line).unwrap();
+
+        let solutions = sudoku.solutions().map(|s| s.to_line()).collect::<Vec<_>>();
+        assert_eq!(solutions.len(), 2);
+        assert_eq!(solutions[0], sudoku.solve().unwrap().to_line());
+        assert_eq!(&solutions[0][..2], "34");
+        assert_eq!(&solutions[1][..2], "53");
+        assert_eq!(solutions[1], SOLUTION);
+
+        let empty = Sudoku::from_line(&".".repeat(81)).unwrap();
+        let first = empty.solutions().take(3).collect::<Vec<_>>();
+        assert_eq!(first, empty.solutions().take(3).collect::<Vec<_>>());
+        assert_eq!(first[0], empty.solve().unwrap());
+        assert!(first.iter().all(|s| s.clone().validate().is_ok()));
+        assert_ne!(first[0], first[1]);
+
+        let solved = Sudoku::from_line(SOLUTION).unwrap();
+        assert_eq!(solved.solutions().collect::<Vec<_>>(), [solved]);
+        assert_eq!(
+            Sudoku::from_line(&PUZZLE.replacen('.', "5", 1))
+                .unwrap()
+                .solutions()
+                .count(),
+            0
+        );
+    }
+
+    #[cfg(feature = "unstable")]
+    #[test]
+    fn test_solve_steps() {
+        let puzzle = Sudoku::from_line(
+            "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        )
+        .unwrap();
+        let mut replay = puzzle.clone();
+        let mut kinds = Vec::new();
+        for step in puzzle.solve_steps() {
+            match step.kind {
+                StepKind::Backtrack => {
+                    assert_eq!(replay[step.position], step.value);
+                    replay[step.position] = EMPTY;
+                }
+                _ => {
+                    assert_eq!(replay[step.position], EMPTY);
+                    replay[step.position] = step.value;
+                }
+            }
+            assert_eq!(step.candidates.sudoku(), &replay);
+            if kinds.is_empty() {
+                assert_eq!(step.candidates, replay.candidates());
+            }
+            kinds.push(step.kind);
+        }
+        assert_eq!(Some(replay), puzzle.solve());
+        assert!(kinds.contains(&StepKind::Guess));
+        assert!(kinds.contains(&StepKind::Backtrack));
+        assert_ne!(kinds.last(), Some(&StepKind::Backtrack));
+
+        let easy = Sudoku::from_line(PUZZLE).unwrap();
+        let steps = easy.solve_steps().collect::<Vec<_>>();
+        assert_eq!(steps.len(), 51);
+        assert!(steps.iter().all(|step| step.kind == StepKind::Deduction));
+        assert_eq!(steps[50].candidates.sudoku().to_line(), SOLUTION);
+
+        let contradiction = Sudoku::from_line(&PUZZLE.replacen('.', "5", 1)).unwrap();
+        assert_eq!(contradiction.solve_steps().count(), 0);
+    }
+
+    #[cfg(all(feature = "std", feature = "unstable"))]
+    #[test]
+    fn test_solve_stream() {
+        let input = format!("# comment\n{PUZZLE}\n\n{}\n{PUZZLE}\r\n", &PUZZLE[1..]);
+        let mut output = Vec::new();
+
+        let summary =
+            solve_stream(input.as_bytes(), &mut output, &StreamConfig::default()).unwrap();
+
+        assert_eq!(
+            summary,
+            StreamSummary {
+                solved: 2,
+                failed: 1
+            }
+        );
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("{SOLUTION}\nerror\n{SOLUTION}\n")
+        );
+    }
+}
@@ -0,0 +1,451 @@
+//! Human-style solving techniques: each one is a logical deduction a person
+//! can make and explain, unlike the guesses of the backtracking solver
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    candidates::{digits, sees, CandidateGrid, ALL_CANDIDATES},
+    Indexes, Sudoku, EMPTY, SUDOKU_SIZE,
+};
+
+const UNITS_COUNT: usize = SUDOKU_SIZE * 3;
+
+/// Ordered from the simplest to the most advanced
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum Technique {
+    /// The cell has only one candidate left
+    NakedSingle,
+    /// The digit fits only one cell of a row, column or box
+    HiddenSingle,
+    /// Two cells of a unit share the same two candidates, so no other
+    /// cell of the unit can hold them
+    NakedPair,
+    /// Inside a box a digit fits only one row (or column), so it can't appear
+    /// in that row (or column) outside of the box
+    PointingPair,
+    /// Inside a row (or column) a digit fits only one box, so it can't appear
+    /// in that box outside of the row (or column)
+    BoxLineReduction,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 5] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::NakedPair,
+        Technique::PointingPair,
+        Technique::BoxLineReduction,
+    ];
+}
+
+/// One deduction: either a placed digit or some removed candidates
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Step {
+    pub technique: Technique,
+    /// Cell filled by the step and its value
+    pub placement: Option<((usize, usize), u8)>,
+    /// Candidates ruled out by the step, as cell and digit
+    pub eliminations: Vec<((usize, usize), u8)>,
+    /// Cells the deduction relies on
+    pub cause: Indexes,
+}
+
+fn unit_cells(unit: usize) -> [(usize, usize); SUDOKU_SIZE] {
+    let i = unit % SUDOKU_SIZE;
+    core::array::from_fn(|j| match unit / SUDOKU_SIZE {
+        0 => (i, j),
+        1 => (j, i),
+        _ => ((i / 3) * 3 + j / 3, (i % 3) * 3 + j % 3),
+    })
+}
+
+/// A grid together with the candidates of its empty cells, so that eliminations
+/// made by one technique are kept for the next ones
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LogicalGrid {
+    grid: CandidateGrid,
+}
+
+impl LogicalGrid {
+    pub fn new(sudoku: &Sudoku) -> Self {
+        Self {
+            grid: sudoku.candidates(),
+        }
+    }
+
+    pub fn sudoku(&self) -> &Sudoku {
+        self.grid.sudoku()
+    }
+
+    pub fn into_sudoku(self) -> Sudoku {
+        self.grid.into_sudoku()
+    }
+
+    /// Candidates of a cell as a bitmask, bit `d - 1` standing for digit `d`,
+    /// `0` for a filled cell
+    pub fn candidates(&self, row: usize, col: usize) -> u16 {
+        self.grid.candidates[row][col]
+    }
+
+    /// Candidates with the eliminations made so far
+    pub fn candidate_grid(&self) -> &CandidateGrid {
+        &self.grid
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.sudoku().cells().all(|(_, value)| value != EMPTY)
+    }
+
+    /// First filled peer of `position` holding `value`
+    fn witness(&self, position: (usize, usize), value: u8) -> Option<(usize, usize)> {
+        self.sudoku()
+            .cells()
+            .find(|(other, other_value)| *other_value == value && sees(position, *other))
+            .map(|(other, _)| other)
+    }
+
+    /// Find the first deduction made by `technique` and apply it
+    pub fn apply(&mut self, technique: Technique) -> Option<Step> {
+        let step = match technique {
+            Technique::NakedSingle => self.naked_single(),
+            Technique::HiddenSingle => self.hidden_single(),
+            Technique::NakedPair => self.naked_pair(),
+            Technique::PointingPair => self.pointing_pair(),
+            Technique::BoxLineReduction => self.box_line_reduction(),
+        }?;
+
+        if let Some((position, value)) = step.placement {
+            self.grid.place(position, value);
+        }
+        for (position, digit) in &step.eliminations {
+            self.grid.eliminate(*position, *digit);
+        }
+        Some(step)
+    }
+
+    /// Apply the simplest technique that makes progress
+    pub fn step(&mut self) -> Option<Step> {
+        Technique::ALL
+            .into_iter()
+            .find_map(|technique| self.apply(technique))
+    }
+
+    fn naked_single(&self) -> Option<Step> {
+        let (position, mask) = self
+            .grid
+            .empty_cells()
+            .find(|(_, mask)| mask.count_ones() == 1)?;
+        let value = digits(mask).next()?;
+
+        Some(Step {
+            technique: Technique::NakedSingle,
+            placement: Some((position, value)),
+            eliminations: vec![],
+            cause: digits(ALL_CANDIDATES & !mask)
+                .filter_map(|other| self.witness(position, other))
+                .collect(),
+        })
+    }
+
+    fn hidden_single(&self) -> Option<Step> {
+        for unit in 0..UNITS_COUNT {
+            let cells = unit_cells(unit);
+            for value in 1..=SUDOKU_SIZE as u8 {
+                let bit = 1 << (value - 1);
+                let mut fitting = cells
+                    .iter()
+                    .filter(|(row, col)| self.grid.candidates[*row][*col] & bit != 0);
+                let (Some(&position), None) = (fitting.next(), fitting.next()) else {
+                    continue;
+                };
+                // A naked single found from the other side is still reported as such
+                if self.grid.candidates[position.0][position.1].count_ones() == 1 {
+                    continue;
+                }
+
+                return Some(Step {
+                    technique: Technique::HiddenSingle,
+                    placement: Some((position, value)),
+                    eliminations: vec![],
+                    cause: cells
+                        .iter()
+                        .filter(|other| **other != position && self.grid.sudoku[**other] == EMPTY)
+                        .filter_map(|other| self.witness(*other, value))
+                        .fold(Indexes::default(), |mut cause, witness| {
+                            if !cause.contains(&witness) {
+                                cause.push(witness);
+                            }
+                            cause
+                        }),
+                });
+            }
+        }
+        None
+    }
+
+    fn eliminations_in(
+        &self,
+        cells: impl Iterator<Item = (usize, usize)>,
+        mask: u16,
+    ) -> Vec<((usize, usize), u8)> {
+        cells
+            .flat_map(|(row, col)| {
+                digits(self.grid.candidates[row][col] & mask).map(move |digit| ((row, col), digit))
+            })
+            .collect()
+    }
+
+    fn naked_pair(&self) -> Option<Step> {
+        for unit in 0..UNITS_COUNT {
+            let cells = unit_cells(unit);
+            for (i, first) in cells.iter().enumerate() {
+                let mask = self.grid.candidates[first.0][first.1];
+                if mask.count_ones() != 2 {
+                    continue;
+                }
+                let Some(second) = cells[i + 1..]
+                    .iter()
+                    .find(|(row, col)| self.grid.candidates[*row][*col] == mask)
+                else {
+                    continue;
+                };
+
+                let eliminations = self.eliminations_in(
+                    cells
+                        .iter()
+                        .copied()
+                        .filter(|cell| cell != first && cell != second),
+                    mask,
+                );
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique: Technique::NakedPair,
+                        placement: None,
+                        eliminations,
+                        cause: [*first, *second].into_iter().collect(),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Cells of `unit` where `value` is a candidate
+    fn fitting(&self, unit: usize, value: u8) -> Indexes {
+        unit_cells(unit)
+            .into_iter()
+            .filter(|(row, col)| self.grid.candidates[*row][*col] & (1 << (value - 1)) != 0)
+            .collect()
+    }
+
+    /// `value` fits only the cells of `source` that also belong to `target`,
+    /// so it is removed from the rest of `target`
+    fn confined(&self, technique: Technique, source: usize, targets: &[usize]) -> Option<Step> {
+        for value in 1..=SUDOKU_SIZE as u8 {
+            let fitting = self.fitting(source, value);
+            if fitting.is_empty() {
+                continue;
+            }
+            for &target in targets {
+                let target_cells = unit_cells(target);
+                if !fitting.iter().all(|cell| target_cells.contains(cell)) {
+                    continue;
+                }
+                let eliminations = self.eliminations_in(
+                    target_cells
+                        .into_iter()
+                        .filter(|cell| !fitting.contains(cell)),
+                    1 << (value - 1),
+                );
+                if !eliminations.is_empty() {
+                    return Some(Step {
+                        technique,
+                        placement: None,
+                        eliminations,
+                        cause: fitting,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn pointing_pair(&self) -> Option<Step> {
+        (0..SUDOKU_SIZE).find_map(|box_index| {
+            let unit = 2 * SUDOKU_SIZE + box_index;
+            let rows = (0..3).map(|i| (box_index / 3) * 3 + i);
+            let columns = (0..3).map(|i| SUDOKU_SIZE + (box_index % 3) * 3 + i);
+            self.confined(
+                Technique::PointingPair,
+                unit,
+                &rows.chain(columns).collect::<Vec<_>>(),
+            )
+        })
+    }
+
+    fn box_line_reduction(&self) -> Option<Step> {
+        (0..2 * SUDOKU_SIZE).find_map(|unit| {
+            let boxes = match unit / SUDOKU_SIZE {
+                0 => (0..3)
+                    .map(|i| ((unit % SUDOKU_SIZE) / 3) * 3 + i)
+                    .collect::<Vec<_>>(),
+                _ => (0..3).map(|i| i * 3 + (unit % SUDOKU_SIZE) / 3).collect(),
+            };
+            self.confined(
+                Technique::BoxLineReduction,
+                unit,
+                &boxes
+                    .into_iter()
+                    .map(|box_index| 2 * SUDOKU_SIZE + box_index)
+                    .collect::<Vec<_>>(),
+            )
+        })
+    }
+}
+
+/// Next cell that can be filled by logic alone, with the explanation
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Hint {
+    pub position: (usize, usize),
+    pub value: u8,
+    /// Technique placing the value
+    pub technique: Technique,
+    /// Cells the placement relies on
+    pub cause: Indexes,
+    /// Candidate eliminations that have to be made before the placement
+    /// becomes visible, in the order they are found
+    pub preparation: Vec<Step>,
+}
+
+impl Sudoku {
+    /// Next logical move: always the simplest technique that makes progress is used,
+    /// eliminations are chained until one of them reveals a placement.
+    ///
+    /// Returns `None` for a full grid or when the known techniques get stuck
+    ///
+    /// ```
+    /// use sudoku_tester::{techniques::Technique, Sudoku};
+    ///
+    /// let sudoku = Sudoku::from_line(
+    ///     "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+    /// )
+    /// .unwrap();
+    /// let hint = sudoku.hint().unwrap();
+    /// assert_eq!(hint.technique, Technique::NakedSingle);
+    /// assert_eq!(sudoku.solve().unwrap()[hint.position], hint.value);
+    /// ```
+    pub fn hint(&self) -> Option<Hint> {
+        let mut grid = LogicalGrid::new(self);
+        let mut preparation = vec![];
+
+        while let Some(step) = grid.step() {
+            match step.placement {
+                Some((position, value)) => {
+                    return Some(Hint {
+                        position,
+                        value,
+                        technique: step.technique,
+                        cause: step.cause,
+                        preparation,
+                    })
+                }
+                None => preparation.push(step),
+            }
+        }
+        None
+    }
+
+    /// Look for the first deduction `technique` allows on this grid and return it,
+    /// a placed digit is written into the grid.
+    ///
+    /// Candidates are computed from scratch every call, so eliminations are only
+    /// reported. Use [`LogicalGrid`] to chain techniques and keep the eliminations
+    pub fn apply_technique(&mut self, technique: Technique) -> Option<Step> {
+        let step = LogicalGrid::new(self).apply(technique)?;
+        if let Some((position, value)) = step.placement {
+            self[position] = value;
+        }
+        Some(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    #[test]
+    fn test_apply_technique() {
+        let mut sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let step = sudoku.apply_technique(Technique::NakedSingle).unwrap();
+
+        let (position, value) = step.placement.unwrap();
+        assert_eq!(sudoku[position], value);
+        assert_eq!(
+            SOLUTION.as_bytes()[position.0 * 9 + position.1],
+            b'0' + value
+        );
+        assert!(!step.cause.is_empty());
+    }
+
+    #[test]
+    fn test_singles_solve_easy_puzzle() {
+        let mut grid = LogicalGrid::new(&Sudoku::from_line(PUZZLE).unwrap());
+        while let Some(step) = grid.step() {
+            assert!(step.technique <= Technique::HiddenSingle);
+        }
+
+        assert!(grid.is_solved());
+        assert_eq!(grid.into_sudoku().to_line(), SOLUTION);
+    }
+
+    #[test]
+    fn test_hint() {
+        let puzzle = Sudoku::from_line(
+            "4.....938.32.941...953..24.37.6.9..4529..16736.47.3.9.957..83....39..4..24..3.7.9",
+        )
+        .unwrap();
+        let solution = puzzle.solve().unwrap();
+
+        let mut sudoku = puzzle.clone();
+        let mut prepared = false;
+        while let Some(hint) = sudoku.hint() {
+            assert_eq!(solution[hint.position], hint.value);
+            assert!(hint.cause.iter().all(|cell| *cell != hint.position));
+            prepared |= !hint.preparation.is_empty();
+            sudoku[hint.position] = hint.value;
+        }
+
+        assert_eq!(sudoku, solution);
+        assert!(prepared);
+        assert_eq!(solution.hint(), None);
+    }
+
+    #[test]
+    fn test_eliminations() {
+        // Needs pairs and intersections before singles can finish it
+        let puzzle = Sudoku::from_line(
+            "4.....938.32.941...953..24.37.6.9..4529..16736.47.3.9.957..83....39..4..24..3.7.9",
+        )
+        .unwrap();
+        let solution = puzzle.solve().unwrap();
+
+        let mut grid = LogicalGrid::new(&puzzle);
+        let mut used = vec![];
+        while let Some(step) = grid.step() {
+            for (position, digit) in &step.eliminations {
+                assert_ne!(solution[*position], *digit, "{step:?}");
+            }
+            used.push(step.technique);
+        }
+
+        assert!(grid.is_solved());
+        assert_eq!(grid.into_sudoku(), solution);
+        assert!(used
+            .iter()
+            .any(|technique| *technique > Technique::HiddenSingle));
+    }
+}
@@ -0,0 +1,140 @@
+//! Invariants of the crate packaged as assertions, so crates embedding this one
+//! can check them in their own integration tests (`test-support` feature).
+//!
+//! Every helper panics with a descriptive message when the invariant is broken
+use crate::{Sudoku, ValidationProfile, EMPTY, SUDOKU_SIZE};
+
+/// Naive solver: tries every digit in every empty cell in row-major order,
+/// without any heuristic. Slow but simple enough to be obviously correct,
+/// returns at most `limit` solutions
+pub fn reference_solutions(sudoku: &Sudoku, limit: usize) -> Vec<Sudoku> {
+    fn fits(grid: &Sudoku, (row, col): (usize, usize), value: u8) -> bool {
+        (0..SUDOKU_SIZE).all(|i| {
+            let (box_row, box_col) = ((row / 3) * 3 + i / 3, (col / 3) * 3 + i % 3);
+            grid.grid[row][i] != value
+                && grid.grid[i][col] != value
+                && grid.grid[box_row][box_col] != value
+        })
+    }
+
+    fn search(grid: &mut Sudoku, limit: usize, solutions: &mut Vec<Sudoku>) {
+        let Some((position, _)) = grid.cells().find(|(_, value)| *value == EMPTY) else {
+            solutions.push(grid.clone());
+            return;
+        };
+        for value in 1..=SUDOKU_SIZE as u8 {
+            if solutions.len() >= limit {
+                return;
+            }
+            if fits(grid, position, value) {
+                grid[position] = value;
+                search(grid, limit, solutions);
+                grid[position] = EMPTY;
+            }
+        }
+    }
+
+    let mut solutions = vec![];
+    if limit > 0
+        && sudoku
+            .clone()
+            .validate_profile(ValidationProfile::Partial)
+            .is_ok()
+    {
+        search(&mut sudoku.clone(), limit, &mut solutions);
+    }
+    solutions
+}
+
+/// Every lossless representation of the grid reads back as the same grid
+pub fn assert_round_trip(sudoku: &Sudoku) {
+    let line = sudoku.to_line();
+    assert_eq!(
+        Sudoku::from_line(&line).as_ref(),
+        Ok(sudoku),
+        "one-line format round trip of {line}"
+    );
+
+    if let Ok(compressed) = sudoku.compress_solution() {
+        assert_eq!(
+            Sudoku::decompress_solution(&compressed).as_ref(),
+            Ok(sudoku),
+            "compression round trip of {line}"
+        );
+    }
+}
+
+/// A solved grid passes every check of a complete sudoku
+pub fn assert_solved_grid_validates(solution: &Sudoku) {
+    if let Err(errors) = solution
+        .clone()
+        .validate_profile(ValidationProfile::Complete)
+    {
+        panic!("solved grid {} is invalid: {errors:?}", solution.to_line());
+    }
+}
+
+/// The solver agrees with [`reference_solutions`]: it finds a solution exactly when
+/// one exists, the solution keeps every given and is valid, and solutions are
+/// counted right
+pub fn assert_solver_correct(puzzle: &Sudoku) {
+    let line = puzzle.to_line();
+    let reference = reference_solutions(puzzle, 2);
+
+    match puzzle.solve() {
+        Some(solution) => {
+            assert!(!reference.is_empty(), "solved unsolvable puzzle {line}");
+            assert_solved_grid_validates(&solution);
+            for (position, value) in puzzle.cells().filter(|(_, value)| *value != EMPTY) {
+                assert_eq!(
+                    solution[position], value,
+                    "solution of {line} overwrites the given at {position:?}"
+                );
+            }
+        }
+        None => assert!(reference.is_empty(), "failed to solve puzzle {line}"),
+    }
+
+    assert_eq!(
+        puzzle.count_solutions(2),
+        reference.len(),
+        "wrong number of solutions for {line}"
+    );
+    if reference.len() == 1 {
+        assert_eq!(puzzle.solve().as_ref(), reference.first());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_invariants() {
+        let puzzle = Sudoku::from_line(PUZZLE).unwrap();
+        assert_round_trip(&puzzle);
+        assert_solver_correct(&puzzle);
+
+        let solution = puzzle.solve().unwrap();
+        assert_round_trip(&solution);
+        assert_solved_grid_validates(&solution);
+
+        let mut ambiguous = puzzle.clone();
+        ambiguous[(0, 0)] = EMPTY;
+        ambiguous[(0, 1)] = EMPTY;
+        assert_solver_correct(&ambiguous);
+
+        let mut unsolvable = puzzle;
+        unsolvable[(0, 2)] = 1;
+        assert_solver_correct(&unsolvable);
+    }
+
+    #[test]
+    #[should_panic(expected = "is invalid")]
+    fn test_invalid_solution() {
+        assert_solved_grid_validates(&Sudoku::from_line(PUZZLE).unwrap());
+    }
+}
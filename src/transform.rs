@@ -0,0 +1,512 @@
+//! Validity-preserving transformations of the grid: the ones mapping every
+//! valid sudoku to a valid sudoku, and every puzzle to an equally hard one.
+//!
+//! Grids related by such a transformation are isomorphic, [`Sudoku::canonical_form`]
+//! picks one representative of each class for deduplication
+use core::cmp::Ordering;
+
+use crate::{rng::Rng, Sudoku, EMPTY, SUDOKU_SIZE};
+
+/// The 6 orders of three lines (or bands)
+const ORDERS: [[usize; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+const REVERSED: [usize; SUDOKU_SIZE] = [8, 7, 6, 5, 4, 3, 2, 1, 0];
+
+/// Mirror axis of a [`Transformation::reflection`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Reflection {
+    /// Top and bottom swap
+    Horizontal,
+    /// Left and right swap
+    Vertical,
+    /// Top-left to bottom-right diagonal, i.e. the transposition
+    MainDiagonal,
+    /// Top-right to bottom-left diagonal
+    AntiDiagonal,
+}
+
+/// Any combination of a transposition, a permutation of rows (within and
+/// between bands), a permutation of columns (within and between stacks)
+/// and a relabeling of the digits
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Transformation {
+    transpose: bool,
+    /// Row `i` of the result is taken from row `rows[i]` of the source
+    rows: [usize; SUDOKU_SIZE],
+    columns: [usize; SUDOKU_SIZE],
+    /// Value `v` of the source becomes `digits[v]`, `digits[EMPTY]` is always `EMPTY`
+    digits: [u8; SUDOKU_SIZE + 1],
+}
+
+impl Default for Transformation {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+/// Order of lines within bands (or stacks) combined with order of the bands
+fn line_permutation(bands: [usize; 3], lines: [[usize; 3]; 3]) -> [usize; SUDOKU_SIZE] {
+    core::array::from_fn(|i| bands[i / 3] * 3 + lines[i / 3][i % 3])
+}
+
+fn random_line_permutation(rng: &mut Rng) -> [usize; SUDOKU_SIZE] {
+    let mut bands = [0, 1, 2];
+    rng.shuffle(&mut bands);
+    let mut lines = [[0, 1, 2]; 3];
+    lines.iter_mut().for_each(|band| rng.shuffle(band));
+    line_permutation(bands, lines)
+}
+
+fn is_order(order: &[usize; 3]) -> bool {
+    ORDERS.contains(order)
+}
+
+fn invert(permutation: &[usize; SUDOKU_SIZE]) -> [usize; SUDOKU_SIZE] {
+    let mut inverse = [0; SUDOKU_SIZE];
+    permutation
+        .iter()
+        .enumerate()
+        .for_each(|(i, source)| inverse[*source] = i);
+    inverse
+}
+
+impl Transformation {
+    pub fn identity() -> Self {
+        Self {
+            transpose: false,
+            rows: core::array::from_fn(|i| i),
+            columns: core::array::from_fn(|i| i),
+            digits: core::array::from_fn(|i| i as u8),
+        }
+    }
+
+    /// Pseudo-random transformation fully determined by `seed`,
+    /// the same seed gives the same transformation on every platform
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = Rng::new(seed);
+
+        let mut digits: [u8; SUDOKU_SIZE + 1] = core::array::from_fn(|i| i as u8);
+        rng.shuffle(&mut digits[1..]);
+
+        Self {
+            transpose: rng.below(2) == 1,
+            rows: random_line_permutation(&mut rng),
+            columns: random_line_permutation(&mut rng),
+            digits,
+        }
+    }
+
+    pub fn apply(&self, sudoku: &Sudoku) -> Sudoku {
+        let mut result = Sudoku::empty();
+        for (row, source_row) in self.rows.iter().enumerate() {
+            for (col, source_col) in self.columns.iter().enumerate() {
+                let value = match self.transpose {
+                    true => sudoku.grid[*source_col][*source_row],
+                    false => sudoku.grid[*source_row][*source_col],
+                };
+                result.grid[row][col] = self.digits.get(value as usize).copied().unwrap_or(value);
+            }
+        }
+        result
+    }
+
+    /// Clockwise rotation by `quarter_turns` times 90 degrees
+    pub fn rotation(quarter_turns: u32) -> Self {
+        let quarter = Self {
+            transpose: true,
+            columns: REVERSED,
+            ..Self::identity()
+        };
+        (0..quarter_turns % 4).fold(Self::identity(), |rotation, _| rotation.then(&quarter))
+    }
+
+    pub fn reflection(axis: Reflection) -> Self {
+        let identity = Self::identity();
+        match axis {
+            Reflection::Horizontal => Self {
+                rows: REVERSED,
+                ..identity
+            },
+            Reflection::Vertical => Self {
+                columns: REVERSED,
+                ..identity
+            },
+            Reflection::MainDiagonal => Self {
+                transpose: true,
+                ..identity
+            },
+            Reflection::AntiDiagonal => Self {
+                transpose: true,
+                rows: REVERSED,
+                columns: REVERSED,
+                ..identity
+            },
+        }
+    }
+
+    /// Band `i` of the result is band `bands[i]` of the source, with its rows
+    /// taken in the order `lines[i]`. `None` unless all of them are orders of `0..3`
+    pub fn permute_rows(bands: [usize; 3], lines: [[usize; 3]; 3]) -> Option<Self> {
+        (is_order(&bands) && lines.iter().all(is_order)).then(|| Self {
+            rows: line_permutation(bands, lines),
+            ..Self::identity()
+        })
+    }
+
+    /// Same as [`Transformation::permute_rows`] for stacks and columns
+    pub fn permute_columns(stacks: [usize; 3], lines: [[usize; 3]; 3]) -> Option<Self> {
+        (is_order(&stacks) && lines.iter().all(is_order)).then(|| Self {
+            columns: line_permutation(stacks, lines),
+            ..Self::identity()
+        })
+    }
+
+    /// Digit `d` becomes `digits[d - 1]`, `None` unless `digits` is an order of `1..=9`
+    pub fn relabel(digits: [u8; SUDOKU_SIZE]) -> Option<Self> {
+        let seen = digits.iter().try_fold(0u16, |seen, digit| {
+            let bit = 1u16.checked_shl(u32::from(digit.checked_sub(1)?))?;
+            Some(seen | bit).filter(|_| seen & bit == 0 && usize::from(*digit) <= SUDOKU_SIZE)
+        });
+        seen.map(|_| Self {
+            digits: core::array::from_fn(|value| match value {
+                0 => EMPTY,
+                value => digits[value - 1],
+            }),
+            ..Self::identity()
+        })
+    }
+
+    /// This transformation followed by `next`
+    pub fn then(&self, next: &Self) -> Self {
+        // A transposing `next` reads the rows of this result as columns
+        let (rows, columns) = match next.transpose {
+            true => (&self.columns, &self.rows),
+            false => (&self.rows, &self.columns),
+        };
+
+        Self {
+            transpose: self.transpose != next.transpose,
+            rows: next.rows.map(|row| rows[row]),
+            columns: next.columns.map(|col| columns[col]),
+            digits: self.digits.map(|digit| next.digits[digit as usize]),
+        }
+    }
+
+    /// Transformation undoing this one
+    pub fn inverse(&self) -> Self {
+        let mut digits = [EMPTY; SUDOKU_SIZE + 1];
+        self.digits
+            .iter()
+            .enumerate()
+            .for_each(|(source, target)| digits[*target as usize] = source as u8);
+
+        let (rows, columns) = match self.transpose {
+            true => (invert(&self.columns), invert(&self.rows)),
+            false => (invert(&self.rows), invert(&self.columns)),
+        };
+
+        Self {
+            transpose: self.transpose,
+            rows,
+            columns,
+            digits,
+        }
+    }
+}
+
+type Grid = [[u8; SUDOKU_SIZE]; SUDOKU_SIZE];
+
+/// Smallest grid reachable by ordering the rows of `lines` band by band,
+/// with digits relabeled in order of first appearance. Branches whose prefix
+/// is already bigger than `best` are cut, a smaller prefix replaces it.
+///
+/// A complete grid is always equal to `best` at that point, its row order and
+/// labels are passed to `leaf`: the last call is the one that built `best`
+fn canonical_rows(
+    lines: &Grid,
+    depth: usize,
+    sources: &mut [usize; SUDOKU_SIZE],
+    labels: [u8; SUDOKU_SIZE + 1],
+    best: &mut Grid,
+    leaf: &mut dyn FnMut(&[usize; SUDOKU_SIZE], &[u8; SUDOKU_SIZE + 1]),
+) {
+    if depth == SUDOKU_SIZE {
+        leaf(sources, &labels);
+        return;
+    }
+    let used = |row: usize| sources[..depth].contains(&row);
+    let candidates = (0..SUDOKU_SIZE)
+        .filter(|row| match depth % 3 {
+            // A new band starts, any row of an unused band can open it
+            0 => (0..3).all(|offset| !used(row / 3 * 3 + offset)),
+            _ => row / 3 == sources[depth - 1] / 3 && !used(*row),
+        })
+        .fold(0u16, |candidates, row| candidates | 1 << row);
+
+    for source in (0..SUDOKU_SIZE).filter(|row| candidates & 1 << row != 0) {
+        let mut labels = labels;
+        let mut next_label = labels.iter().copied().max().unwrap_or(EMPTY) + 1;
+        let row = lines[source].map(|value| match labels.get_mut(value as usize) {
+            Some(label) if value != EMPTY && *label == EMPTY => {
+                *label = next_label;
+                next_label += 1;
+                *label
+            }
+            Some(label) => *label,
+            // Out of range values aren't digits, they stay as they are
+            None => value,
+        });
+
+        match row.cmp(&best[depth]) {
+            Ordering::Greater => continue,
+            Ordering::Less => {
+                best[depth] = row;
+                best[depth + 1..].fill([u8::MAX; SUDOKU_SIZE]);
+            }
+            Ordering::Equal => {}
+        }
+        sources[depth] = source;
+        canonical_rows(lines, depth + 1, sources, labels, best, leaf);
+    }
+}
+
+/// Relabeling of every digit from the labels of the digits the grid holds,
+/// the missing ones take the labels left in ascending order
+fn complete_labels(labels: &[u8; SUDOKU_SIZE + 1]) -> [u8; SUDOKU_SIZE + 1] {
+    let mut free = (1..=SUDOKU_SIZE as u8).filter(|label| !labels[1..].contains(label));
+    let mut digits = *labels;
+    digits[1..]
+        .iter_mut()
+        .filter(|label| **label == EMPTY)
+        .for_each(|label| *label = free.next().unwrap_or(EMPTY));
+    digits
+}
+
+impl Sudoku {
+    /// Representative of all grids isomorphic to this one: the smallest grid, read
+    /// row by row with empty cells as `0`, among every [`Transformation`] of it.
+    ///
+    /// Two grids are isomorphic exactly when their canonical forms are equal
+    pub fn canonical_form(&self) -> Sudoku {
+        self.canonicalize().0
+    }
+
+    /// A [`Transformation`] turning this grid into its [`Sudoku::canonical_form`].
+    ///
+    /// Applying it to another grid aligns that grid the same way, e.g. a puzzle
+    /// through the transformation canonicalizing its solution
+    pub fn canonical_transformation(&self) -> Transformation {
+        self.canonicalize().1
+    }
+
+    fn canonicalize(&self) -> (Sudoku, Transformation) {
+        let mut best = [[u8::MAX; SUDOKU_SIZE]; SUDOKU_SIZE];
+        let mut found = Transformation::identity();
+        let transposed = Transformation::reflection(Reflection::MainDiagonal).apply(self);
+
+        for (transpose, source) in [(false, self), (true, &transposed)] {
+            for stacks in ORDERS {
+                for lines in ORDERS.iter().flat_map(|first| {
+                    ORDERS.iter().flat_map(move |second| {
+                        ORDERS.iter().map(move |third| [*first, *second, *third])
+                    })
+                }) {
+                    let columns = line_permutation(stacks, lines);
+                    let lines = source.grid.map(|row| columns.map(|col| row[col]));
+                    canonical_rows(
+                        &lines,
+                        0,
+                        &mut [0; SUDOKU_SIZE],
+                        [EMPTY; SUDOKU_SIZE + 1],
+                        &mut best,
+                        &mut |rows, labels| {
+                            found = Transformation {
+                                transpose,
+                                rows: *rows,
+                                columns,
+                                digits: complete_labels(labels),
+                            }
+                        },
+                    );
+                }
+            }
+        }
+
+        (Sudoku { grid: best }, found)
+    }
+
+    /// Whether `other` is this grid up to a [`Transformation`]
+    pub fn is_isomorphic_to(&self, other: &Sudoku) -> bool {
+        self.canonical_form() == other.canonical_form()
+    }
+
+    /// Keyed copy of the grid for distribution to one licensee.
+    ///
+    /// The copy is an equivalent sudoku (same validity, same difficulty, same
+    /// number of solutions) that looks unrelated to the original unless `seed` is known
+    pub fn watermark(&self, seed: u64) -> Sudoku {
+        Transformation::from_seed(seed).apply(self)
+    }
+
+    /// Whether this grid is the copy of `original` watermarked with `seed`,
+    /// i.e. whether it was distributed to the licensee holding this `seed`
+    pub fn detect_watermark(&self, original: &Sudoku, seed: u64) -> bool {
+        Transformation::from_seed(seed).inverse().apply(self) == *original
+    }
+
+    /// Equivalent grid safe to share in a bug report: the [`Sudoku::canonical_form`]
+    /// under a random [`Transformation`]. Unlike [`Sudoku::watermark`] the result
+    /// only depends on the isomorphism class and `seed`, so neither the published
+    /// layout nor any watermark of the copy survives
+    pub fn anonymize(&self, seed: u64) -> Sudoku {
+        Transformation::from_seed(seed).apply(&self.canonical_form())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RuleSet;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_transformation_inverse() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+
+        for seed in 0..32 {
+            let transformation = Transformation::from_seed(seed);
+            let transformed = transformation.apply(&sudoku);
+
+            assert_eq!(transformation.inverse().apply(&transformed), sudoku);
+            assert!(transformed
+                .clone()
+                .validate_against(&RuleSet::standard())
+                .is_ok());
+            assert_eq!(
+                transformed.solve().unwrap(),
+                transformation.apply(&sudoku.solve().unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_transformation_constructors() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let at = |sudoku: &Sudoku, row: usize, col: usize| sudoku.grid[row][col];
+
+        let rotated = Transformation::rotation(1).apply(&sudoku);
+        assert_eq!(at(&rotated, 0, 8), at(&sudoku, 0, 0));
+        assert_eq!(at(&rotated, 1, 0), at(&sudoku, 8, 1));
+        assert_eq!(Transformation::rotation(4), Transformation::identity());
+        assert_eq!(
+            Transformation::rotation(2).apply(&sudoku),
+            Transformation::reflection(Reflection::Horizontal)
+                .then(&Transformation::reflection(Reflection::Vertical))
+                .apply(&sudoku)
+        );
+        let anti = Transformation::reflection(Reflection::AntiDiagonal).apply(&sudoku);
+        assert_eq!(at(&anti, 0, 0), at(&sudoku, 8, 8));
+        assert_eq!(at(&anti, 0, 1), at(&sudoku, 7, 8));
+
+        let swapped = Transformation::permute_rows([1, 0, 2], [[0, 1, 2], [2, 1, 0], [0, 1, 2]])
+            .unwrap()
+            .apply(&sudoku);
+        assert_eq!(swapped.grid[0], sudoku.grid[3]);
+        assert_eq!(swapped.grid[3], sudoku.grid[2]);
+        assert!(Transformation::permute_columns([0, 0, 1], [[0, 1, 2]; 3]).is_none());
+
+        let relabeled = Transformation::relabel([2, 3, 4, 5, 6, 7, 8, 9, 1])
+            .unwrap()
+            .apply(&sudoku);
+        assert_eq!(at(&relabeled, 0, 0), 6);
+        assert_eq!(at(&relabeled, 0, 2), EMPTY);
+        assert!(Transformation::relabel([1, 1, 2, 3, 4, 5, 6, 7, 8]).is_none());
+        assert!(Transformation::relabel([0, 1, 2, 3, 4, 5, 6, 7, 8]).is_none());
+    }
+
+    #[test]
+    fn test_then() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        for seed in 0..16 {
+            let first = Transformation::from_seed(seed);
+            let second = Transformation::from_seed(seed + 100);
+            assert_eq!(
+                first.then(&second).apply(&sudoku),
+                second.apply(&first.apply(&sudoku))
+            );
+            assert_eq!(first.then(&first.inverse()), Transformation::identity());
+        }
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let canonical = sudoku.canonical_form();
+
+        for seed in 0..4 {
+            let transformed = Transformation::from_seed(seed)
+                .then(&Transformation::rotation(seed as u32))
+                .apply(&sudoku);
+            assert_eq!(transformed.canonical_form(), canonical);
+            assert_eq!(
+                transformed.canonical_transformation().apply(&transformed),
+                canonical
+            );
+            assert!(transformed.is_isomorphic_to(&sudoku));
+        }
+        assert_eq!(canonical.canonical_form(), canonical);
+        assert_eq!(sudoku.canonical_transformation().apply(&sudoku), canonical);
+        assert_eq!(
+            canonical
+                .cells()
+                .filter(|(_, value)| *value != EMPTY)
+                .count(),
+            sudoku.cells().filter(|(_, value)| *value != EMPTY).count()
+        );
+
+        let mut other = sudoku.clone();
+        other[(0, 0)] = EMPTY;
+        assert!(!other.is_isomorphic_to(&sudoku));
+        other[(0, 0)] = 12;
+        assert_eq!(
+            other
+                .canonical_form()
+                .cells()
+                .filter(|(_, value)| *value == 12)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_watermark() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let watermarked = sudoku.watermark(42);
+
+        assert_eq!(watermarked, sudoku.watermark(42));
+        assert_ne!(watermarked, sudoku);
+        assert!(watermarked.detect_watermark(&sudoku, 42));
+        assert!(!watermarked.detect_watermark(&sudoku, 43));
+        assert!(!sudoku.detect_watermark(&sudoku, 42));
+    }
+
+    #[test]
+    fn test_anonymize() {
+        let sudoku = Sudoku::from_line(PUZZLE).unwrap();
+        let anonymized = sudoku.anonymize(7);
+
+        assert!(anonymized.is_isomorphic_to(&sudoku));
+        assert!(anonymized.has_unique_solution());
+        assert_eq!(sudoku.watermark(42).anonymize(7), anonymized);
+        assert_ne!(sudoku.anonymize(8), anonymized);
+        assert!(!anonymized.detect_watermark(&sudoku, 7));
+    }
+}
@@ -0,0 +1,280 @@
+//! JavaScript bindings (`wasm` feature), packaged by the `bindings` crate of the
+//! workspace.
+//!
+//! Grids cross the boundary as one-line strings (see [`Sudoku::from_line`]).
+//! Failures are thrown as plain objects with a `kind` field plus the details of
+//! the error, never as panics
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    generator::{self, GeneratorConfig},
+    ParseError, RuleSet, Sudoku, ValidationError, ValidationErrorType,
+};
+
+/// Value of an object field, built without JavaScript so the mapping of the
+/// errors can be tested natively, see [`Field::into_js`]
+#[derive(Debug, PartialEq)]
+enum Field {
+    Text(String),
+    Number(u32),
+    /// `[row, column]`
+    Cell((usize, usize)),
+    Cells(Vec<(usize, usize)>),
+    Object(Vec<(&'static str, Field)>),
+}
+
+impl From<&str> for Field {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_owned())
+    }
+}
+
+impl From<u32> for Field {
+    fn from(number: u32) -> Self {
+        Self::Number(number)
+    }
+}
+
+impl Field {
+    fn into_js(self) -> JsValue {
+        match self {
+            Self::Text(text) => text.into(),
+            Self::Number(number) => number.into(),
+            Self::Cell((row, col)) => {
+                Array::of2(&JsValue::from(row as u32), &JsValue::from(col as u32)).into()
+            }
+            Self::Cells(cells) => cells
+                .into_iter()
+                .map(|cell| Self::Cell(cell).into_js())
+                .collect::<Array>()
+                .into(),
+            Self::Object(fields) => {
+                let object = Object::new();
+                for (key, value) in fields {
+                    // Setting a property on a fresh plain object can't fail
+                    let _ = Reflect::set(&object, &JsValue::from_str(key), &value.into_js());
+                }
+                object.into()
+            }
+        }
+    }
+}
+
+/// `{kind, index}`, e.g. `{kind: "Row", index: 3}`
+fn unit(type_: &ValidationErrorType) -> Field {
+    let (kind, index) = match *type_ {
+        ValidationErrorType::Column(index) => ("Column", index),
+        ValidationErrorType::Row(index) => ("Row", index),
+        ValidationErrorType::Box(index) => ("Box", index),
+        ValidationErrorType::Diagonal(index) => ("Diagonal", index),
+        ValidationErrorType::ExtraRegion(index) => ("ExtraRegion", index),
+        ValidationErrorType::Cage(index) => ("Cage", index),
+    };
+    Field::Object(vec![
+        ("kind", kind.into()),
+        ("index", (index as u32).into()),
+    ])
+}
+
+fn parse_error(err: ParseError) -> Field {
+    Field::Object(match err {
+        ParseError::WrongSymbol(symbol) => vec![
+            ("kind", "WrongSymbol".into()),
+            ("symbol", Field::Text(symbol.to_string())),
+        ],
+        ParseError::WrongRowSize { index, len } => vec![
+            ("kind", "WrongRowSize".into()),
+            ("index", (index as u32).into()),
+            ("len", (len as u32).into()),
+        ],
+        ParseError::WrongColumnSize { column_count } => vec![
+            ("kind", "WrongColumnSize".into()),
+            ("columnCount", (column_count as u32).into()),
+        ],
+        ParseError::WrongLineLength { len } => vec![
+            ("kind", "WrongLineLength".into()),
+            ("len", (len as u32).into()),
+        ],
+        ParseError::WrongSymbolAt {
+            symbol,
+            line,
+            column,
+        } => vec![
+            ("kind", "WrongSymbolAt".into()),
+            ("symbol", Field::Text(symbol.to_string())),
+            ("line", (line as u32).into()),
+            ("column", (column as u32).into()),
+        ],
+        ParseError::WrongRowSizeAt { line, len } => vec![
+            ("kind", "WrongRowSizeAt".into()),
+            ("line", (line as u32).into()),
+            ("len", (len as u32).into()),
+        ],
+        ParseError::InvalidUtf8 => vec![("kind", "InvalidUtf8".into())],
+    })
+}
+
+fn validation_error(err: &ValidationError) -> Field {
+    Field::Object(match err {
+        ValidationError::Dublication {
+            type_,
+            value,
+            indexes,
+        } => vec![
+            ("kind", "Duplication".into()),
+            ("unit", unit(type_)),
+            ("value", u32::from(*value).into()),
+            ("cells", Field::Cells(indexes.to_vec())),
+        ],
+        ValidationError::OutOfRange { index, value } => vec![
+            ("kind", "OutOfRange".into()),
+            ("cell", Field::Cell(*index)),
+            ("value", u32::from(*value).into()),
+        ],
+        ValidationError::Incomplete { empty_count } => vec![
+            ("kind", "Incomplete".into()),
+            ("emptyCount", (*empty_count as u32).into()),
+        ],
+        ValidationError::Unsolvable => vec![("kind", "Unsolvable".into())],
+        ValidationError::MultipleSolutions => vec![("kind", "MultipleSolutions".into())],
+        ValidationError::CageSum {
+            cage,
+            expected,
+            actual,
+        } => vec![
+            ("kind", "CageSum".into()),
+            ("cage", (*cage as u32).into()),
+            ("expected", u32::from(*expected).into()),
+            ("actual", u32::from(*actual).into()),
+        ],
+        ValidationError::Violation { rule, indexes } => vec![
+            ("kind", "Violation".into()),
+            ("rule", rule.as_ref().into()),
+            ("cells", Field::Cells(indexes.to_vec())),
+        ],
+    })
+}
+
+/// Either the one-line format or the 9-line grid
+fn parse_grid(input: &str) -> Result<Sudoku, Field> {
+    let input = input.trim();
+    match input.lines().count() {
+        1 => Sudoku::from_line(input),
+        _ => input.parse(),
+    }
+    .map_err(parse_error)
+}
+
+/// Duplicates only, like `sudoku_validate` of the C interface: empty cells of
+/// an unfinished puzzle are not an error
+fn duplicates(input: &str) -> Result<Vec<Field>, Field> {
+    let sudoku = parse_grid(input)?;
+    Ok(RuleSet::standard()
+        .check(&sudoku)
+        .iter()
+        .map(validation_error)
+        .collect())
+}
+
+/// Normalize a grid in any supported text format into the one-line format
+#[wasm_bindgen]
+pub fn parse(input: &str) -> Result<String, JsValue> {
+    parse_grid(input)
+        .map(|sudoku| sudoku.to_line())
+        .map_err(Field::into_js)
+}
+
+/// Array of every duplicate in the grid, empty when there is none. Empty cells
+/// are allowed, so puzzles in progress can be checked
+#[wasm_bindgen]
+pub fn validate(input: &str) -> Result<Array, JsValue> {
+    duplicates(input)
+        .map(|errors| errors.into_iter().map(Field::into_js).collect())
+        .map_err(Field::into_js)
+}
+
+#[wasm_bindgen]
+pub fn solve(input: &str) -> Result<String, JsValue> {
+    parse_grid(input)
+        .map_err(Field::into_js)?
+        .solve()
+        .map(|solution| solution.to_line())
+        .ok_or_else(|| Field::Object(vec![("kind", "Unsolvable".into())]).into_js())
+}
+
+/// Puzzle with a unique solution, `clues` as in [`GeneratorConfig::target_clues`]
+#[wasm_bindgen]
+pub fn generate(seed: u32, clues: u32, symmetric: bool) -> String {
+    generator::generate(&GeneratorConfig {
+        seed: seed.into(),
+        target_clues: clues as usize,
+        symmetric,
+    })
+    .to_line()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_duplicates() {
+        assert_eq!(duplicates(PUZZLE), Ok(vec![]));
+        assert_eq!(
+            duplicates(&PUZZLE.replacen('.', "5", 1)),
+            Ok(vec![
+                Field::Object(vec![
+                    ("kind", "Duplication".into()),
+                    (
+                        "unit",
+                        Field::Object(vec![("kind", "Row".into()), ("index", 0.into())])
+                    ),
+                    ("value", 5.into()),
+                    ("cells", Field::Cells(vec![(0, 0), (0, 2)])),
+                ]),
+                Field::Object(vec![
+                    ("kind", "Duplication".into()),
+                    (
+                        "unit",
+                        Field::Object(vec![("kind", "Box".into()), ("index", 0.into())])
+                    ),
+                    ("value", 5.into()),
+                    ("cells", Field::Cells(vec![(0, 0), (0, 2)])),
+                ]),
+            ])
+        );
+        assert_eq!(
+            duplicates(&PUZZLE.replacen('.', "x", 1)),
+            Err(Field::Object(vec![
+                ("kind", "WrongSymbol".into()),
+                ("symbol", "x".into()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_validation_error_fields() {
+        assert_eq!(
+            validation_error(&ValidationError::OutOfRange {
+                index: (4, 2),
+                value: 12
+            }),
+            Field::Object(vec![
+                ("kind", "OutOfRange".into()),
+                ("cell", Field::Cell((4, 2))),
+                ("value", 12.into()),
+            ])
+        );
+        assert_eq!(
+            validation_error(&ValidationError::Incomplete { empty_count: 51 }),
+            Field::Object(vec![
+                ("kind", "Incomplete".into()),
+                ("emptyCount", 51.into())
+            ])
+        );
+    }
+}